@@ -189,6 +189,7 @@ def BaseStoreDataClass(
     _init_store_cal: callable,
     _set_ele_store: callable,
     _set_block_store: callable,
+    _init_child_store: callable = None,
     _max_len_pubkeys: int = 32,
     _num_try_get_data: int = 10,
 ):
@@ -234,19 +235,70 @@ def BaseStoreDataClass(
                 self.init_store_cal = _init_store_cal
                 self.set_ele_store = _set_ele_store
                 self.set_block_store = _set_block_store
+                self.init_child_store = _init_child_store
 
+                version=HotaUint8(1)
                 depth=HotaUint8(_depth)
-                pubkeys=HotaArrayStruct(_max_len_pubkeys, lambda: HotaPublicKey())
-                status=HotaArrayStruct(_max_len_pubkeys, lambda: HotaUint8(0))
+                fan_out=HotaUint16(_max_len_pubkeys)
+                child_count=HotaUint16(0)
+                occupancy=HotaArrayStruct(32, lambda: HotaUint8(0))
+                authority=HotaPublicKey()
+                delegates=HotaArrayStruct(4, lambda: HotaPublicKey())
+                parcel=HotaPublicKey()
+                world=HotaPublicKey()
+                price=HotaUint64(0)
+                parent=HotaPublicKey()
+                parent_index=HotaUint8(0)
+                creator=HotaPublicKey()
+                kind=HotaUint8(0)
+                origin=HotaArrayStruct(3, lambda: HotaIntX(8, 0))
+                edge=HotaArrayStruct(3, lambda: HotaIntX(8, 0))
+                mapping=HotaUint8(1)
+                lazy=HotaUint8(0)
+                derived=HotaUint8(0)
+                transfer_from=HotaPublicKey()
+                transfer_blocks=HotaUint8(0)
+                transfer_cursor=HotaUint8(0)
+                content_hash=HotaHex(32)
+                chunk_hashes=HotaArrayStruct(32, lambda: HotaHex(32))
+                hashed_at=HotaUint64(0)
+                lod_solid=HotaArrayStruct(32, lambda: HotaUint8(0))
+                lod_materials=HotaVectorStruct(_max_len_pubkeys, lambda: HotaUint8(0), UintLen=HotaUint32(_max_len_pubkeys))
+                pubkeys=HotaVectorStruct(_max_len_pubkeys, lambda: HotaPublicKey(), UintLen=HotaUint32(_max_len_pubkeys))
 
                 # Update kwargs to dict_object
                 for key, value in kwargs.items():
                     if key in dict_object:
                         dict_object[key] = value
                 super().__init__(GenBaseEleList({
+                    "version": version,
                     "depth": depth,
+                    "fan_out": fan_out,
+                    "child_count": child_count,
+                    "occupancy": occupancy,
+                    "authority": authority,
+                    "delegates": delegates,
+                    "parcel": parcel,
+                    "world": world,
+                    "price": price,
+                    "parent": parent,
+                    "parent_index": parent_index,
+                    "creator": creator,
+                    "kind": kind,
+                    "origin": origin,
+                    "edge": edge,
+                    "mapping": mapping,
+                    "lazy": lazy,
+                    "derived": derived,
+                    "transfer_from": transfer_from,
+                    "transfer_blocks": transfer_blocks,
+                    "transfer_cursor": transfer_cursor,
+                    "content_hash": content_hash,
+                    "chunk_hashes": chunk_hashes,
+                    "hashed_at": hashed_at,
+                    "lod_solid": lod_solid,
+                    "lod_materials": lod_materials,
                     "pubkeys": pubkeys,
-                    "status": status,
                     **dict_object
                 }))
 
@@ -260,21 +312,31 @@ def BaseStoreDataClass(
                 self.update_elements()
                 return res
             
+            def is_occupied(self, i: int) -> bool:
+                return (self.get("occupancy").get(i // 8).struct2object() >> (i % 8)) & 1 == 1
+
+            def set_occupied(self, i: int):
+                byte = self.get("occupancy").get(i // 8)
+                byte.object2struct(byte.struct2object() | (1 << (i % 8)))
+
+            def get_pubkey(self, i: int):
+                return self.get("pubkeys").getByIndex(i)
+
             def update_elements(self):
                 self.elements = LazyRPG()
                 for i in range(_max_len_pubkeys):
-                    if self.get("status").get(i).struct2object() == 1:
+                    if self.is_occupied(i):
                         if self.get("depth").struct2object() > 1:
                             self.elements.append(lambda x: self.loop_try(
                                 self.client_rpg.get_account_data_struct,
-                                self.get("pubkeys").get(x).struct2object(),
+                                self.get_pubkey(x).struct2object(),
                                 BaseStoreDataClass,
                                 [8, 0],
                             ))
                         else:
                             self.elements.append(lambda x: self.loop_try(
                                 self.client_rpg.get_account_data_struct,
-                                self.get("pubkeys").get(x).struct2object(),
+                                self.get_pubkey(x).struct2object(),
                                 _block_class,
                                 [8, 0],
                             ))
@@ -309,15 +371,15 @@ def BaseStoreDataClass(
                 if len(ids) != depth:
                     raise Exception(f"Ids must have length = depth. Found ids = {ids}, depth = {depth}")
                 i = ids[0]
-                if self.get("status").get(i).struct2object() == 1:
+                if self.is_occupied(i):
                     if depth > 1:
                         return self.elements[i].get_block_by_ids(ids[1:])
                     else:
-                        return self.get("pubkeys").get(i).struct2object(), self.elements[i]
+                        return self.get_pubkey(i).struct2object(), self.elements[i]
                 else:
                     raise Exception(f"Block not found at index {i} in get_block_by_ids\ndepth = {depth}\nids = {ids}")
             
-            def set_block_by_ids(self, onwer_block: Keypair, ids: list[int], block_pubkey: PublicKey, pre_pubkey: PublicKey):
+            def set_block_by_ids(self, onwer_block: Keypair, ids: list[int], block_pubkey: PublicKey, pre_pubkey: PublicKey, path: list[str] = []):
                 print(f"Set block by ids: {ids}, depth: {self.get('depth').struct2object()}")
 
                 block_pubkey_bs58 = bs58.encode(block_pubkey.byte_value)
@@ -328,25 +390,31 @@ def BaseStoreDataClass(
                 i = ids[0]
                 if depth > 1:
                     # Check if element is not available
-                    if self.get("status").get(i).struct2object() == 0:
-                        new_store_pubkey = self.init_store_cal(depth - 1)["public_key"]
+                    if not self.is_occupied(i):
+                        if self.init_child_store is not None:
+                            # Child address is derived from (parent, index), store is linked on creation
+                            new_store_pubkey = self.init_child_store(pre_pubkey_bs58, i)["public_key"]
+                        else:
+                            new_store_pubkey = self.init_store_cal(depth - 1, _max_len_pubkeys)["public_key"]
                         self.elements[i] = self.loop_try(
                             self.client_rpg.get_account_data_struct,
                             makePublicKey(new_store_pubkey),
                             BaseStoreDataClass,
                             [8, 0],
                         )
-                        self.get("pubkeys").get(i).object2struct(new_store_pubkey)
-                        self.get("status").get(i).object2struct(1)
-                        self.set_ele_store(pre_pubkey_bs58, new_store_pubkey, i)
+                        self.get_pubkey(i).object2struct(new_store_pubkey)
+                        self.set_occupied(i)
+                        if self.init_child_store is None:
+                            self.set_ele_store(pre_pubkey_bs58, new_store_pubkey, i)
                     # Set block
-                    self.elements[i].set_block_by_ids(onwer_block, ids[1:], block_pubkey, PublicKey(self.get("pubkeys").get(i).struct2object()))
+                    self.elements[i].set_block_by_ids(onwer_block, ids[1:], block_pubkey, PublicKey(self.get_pubkey(i).struct2object()), path + [pre_pubkey_bs58])
                 else:
                     self.set_block_store(
                         onwer_block,
                         pre_pubkey_bs58,
                         block_pubkey_bs58,
-                        i
+                        i,
+                        path
                     )
                     self.elements[i] = self.loop_try(
                         self.client_rpg.get_account_data_struct,
@@ -354,8 +422,8 @@ def BaseStoreDataClass(
                         _block_class,
                         [8, 0],
                     )
-                    self.get("pubkeys").get(i).object2struct(block_pubkey_bs58)
-                    self.get("status").get(i).object2struct(1)
+                    self.get_pubkey(i).object2struct(block_pubkey_bs58)
+                    self.set_occupied(i)
                     
                     return True
                 
@@ -19,7 +19,7 @@ hotaSolana API helps you do awesome stuff. 🚀
 
 - block = 8x8x8
 - 1 world max = 32x32x32x32x32x32 block = 8x8x8 x 32x32x32x32x32x32  small block (when init, world is empty)
-- 1 store max = 256 store or 256 block
+- 1 store max = 256 store or 256 block (fan out chosen at init, default 32)
 - 1 item max = 32x32x32 block = 8x8x8 x 32x32x32 small block
 - 1 block = 8x8x8 small block = 512 bytes, so max is 2^8 = 256 type small block
 """
@@ -49,22 +49,72 @@ client = HotaSolanaRPC(programId, False, "devnet")
 # Solana instruction data
 @BaseStructClass
 class XYZ:
-    x=HotaUint64(0)
-    y=HotaUint64(0)
-    z=HotaUint64(0)
+    x=HotaIntX(8, 0)
+    y=HotaIntX(8, 0)
+    z=HotaIntX(8, 0)
 
 @BaseStructClass
 class Block:
+    version=HotaUint8(1)
     owner=HotaPublicKey()
     xyz=XYZ()
     price=HotaUint64(0)
     data=HotaArrayStruct(512, lambda: HotaUint8(0))
+    delegates=HotaArrayStruct(8, lambda: HotaPublicKey())
+    delegate_permissions=HotaArrayStruct(8, lambda: HotaUint8(0))
+    lease_rate=HotaUint64(0)
+    lease_max_slots=HotaUint64(0)
+    renter=HotaPublicKey()
+    lease_expiry=HotaUint64(0)
+    frozen=HotaUint8(0)
+    revision=HotaUint64(0)
+    created_at=HotaUint64(0)
+    updated_at=HotaUint64(0)
+    content_hash=HotaHex(32)
+    history_hash=HotaHex(32)
+    pending_owner=HotaPublicKey()
+    world=HotaPublicKey()
+    store=HotaPublicKey()
+    store_index=HotaUint8(0)
 
 @BaseStructClass
 class Store:
+    version=HotaUint8(1)
     depth=HotaUint8(0)
-    pubkeys=HotaArrayStruct(32, lambda: HotaPublicKey())
-    status=HotaArrayStruct(32, lambda: HotaUint8(0))
+    fan_out=HotaUint16(32)
+    child_count=HotaUint16(0)
+    occupancy=HotaArrayStruct(32, lambda: HotaUint8(0))
+    authority=HotaPublicKey()
+    delegates=HotaArrayStruct(4, lambda: HotaPublicKey())
+    parcel=HotaPublicKey()
+    world=HotaPublicKey()
+    price=HotaUint64(0)
+    parent=HotaPublicKey()
+    parent_index=HotaUint8(0)
+    creator=HotaPublicKey()
+    kind=HotaUint8(0)
+    origin=XYZ()
+    edge=XYZ()
+    mapping=HotaUint8(1)
+    lazy=HotaUint8(0)
+    derived=HotaUint8(0)
+    transfer_from=HotaPublicKey()
+    transfer_blocks=HotaUint8(0)
+    transfer_cursor=HotaUint8(0)
+    content_hash=HotaHex(32)
+    chunk_hashes=HotaArrayStruct(32, lambda: HotaHex(32))
+    hashed_at=HotaUint64(0)
+    lod_solid=HotaArrayStruct(32, lambda: HotaUint8(0))
+    lod_materials=HotaVectorStruct(32, lambda: HotaUint8(0), UintLen=HotaUint32(32))
+    pubkeys=HotaVectorStruct(32, lambda: HotaPublicKey(), UintLen=HotaUint32(32))
+
+@BaseStructClass
+class WorldSettings:
+    root=HotaPublicKey()
+    authority=HotaPublicKey()
+    treasury=HotaPublicKey()
+    parcel_fee=HotaUint64(0)
+    parcel_depth=HotaUint8(1)
 
 # Solana instruction
 @BaseInstructionDataClass("init_block")
@@ -77,12 +127,27 @@ class InitBlockInstruction:
 @BaseInstructionDataClass("init_store")
 class InitStoreInstruction:
     depth=HotaUint8(0)
+    fan_out=HotaUint16(32)
     seed_random=HotaUint128(0)
 
+@BaseInstructionDataClass("init_world")
+class InitWorldInstruction:
+    treasury=HotaPublicKey()
+    parcel_fee=HotaUint64(0)
+    parcel_depth=HotaUint8(1)
+
 @BaseInstructionDataClass("set_ele_store")
 class SetEleStoreInstruction:
     index=HotaUint8(0)
 
+@BaseInstructionDataClass("init_child_store")
+class InitChildStoreInstruction:
+    index=HotaUint8(0)
+
+@BaseInstructionDataClass("create_path")
+class CreatePathInstruction:
+    xyz=XYZ()
+
 @BaseInstructionDataClass("set_block_store")
 class SetBlockStoreInstruction:
     index=HotaUint8(0)
@@ -92,18 +157,22 @@ class UpdateBlockInstruction:
     xyz=XYZ()
     price=HotaUint64(0)
     data=HotaArrayStruct(512, lambda: HotaUint8(0))
+    expected_revision=HotaUint64(0)
 
 @BaseInstructionDataClass("trade_block")
 class TradeBlockInstruction:
-    pass
+    max_price=HotaUint64(0)
+    min_price=HotaUint64(0)
 
 # Store class
 def _init_store_cal(
     depth: int,
+    fan_out: int = 32,
 ):
     # init store instruction
     instruction_data = InitStoreInstruction()
     instruction_data.get("depth").object2struct(depth)
+    instruction_data.get("fan_out").object2struct(fan_out)
     instruction_data.get("seed_random").random()
 
     store_pubkey = findProgramAddress(createBytesFromArrayBytes(
@@ -116,6 +185,8 @@ def _init_store_cal(
     transaction_address = client.send_transaction(
         instruction_data,
         [
+            makeKeyPair(payerPrivateKey).public_key,
+            # Store authority
             makeKeyPair(payerPrivateKey).public_key,
             store_pubkey,
             makePublicKey(sysvar_rent),
@@ -132,10 +203,180 @@ def _init_store_cal(
         "public_key": bs58.encode(store_pubkey.byte_value),
     }
 
+def _world_settings_address(root_store: PublicKey):
+    return findProgramAddress(createBytesFromArrayBytes(
+        "world".encode("utf-8"),
+        root_store.byte_value,
+    ),
+    client.program_id)
+
+def _init_world_cal(
+    depth: int,
+    parcel_depth: int = 1,
+):
+    # Blocks can only be created in roots with world settings
+    res = _init_store_cal(depth)
+    root_store_pubkey = makePublicKey(res["public_key"])
+
+    instruction_data = InitWorldInstruction()
+    instruction_data.get("treasury").object2struct(makeKeyPair(payerPrivateKey).public_key.byte_value)
+    instruction_data.get("parcel_depth").object2struct(parcel_depth)
+
+    client.send_transaction(
+        instruction_data,
+        [
+            makeKeyPair(payerPrivateKey).public_key,
+            # World authority
+            makeKeyPair(payerPrivateKey).public_key,
+            root_store_pubkey,
+            _world_settings_address(root_store_pubkey),
+            makePublicKey(sysvar_rent),
+            makePublicKey(system_program),
+        ],
+        [
+            makeKeyPair(payerPrivateKey),
+        ],
+        makeKeyPair(payerPrivateKey).public_key
+    )
+
+    return res
+
+def _child_store_address(parent: str, index: int):
+    return findProgramAddress(createBytesFromArrayBytes(
+        "store".encode("utf-8"),
+        makePublicKey(parent).byte_value,
+        bytes([index])
+    ),
+    client.program_id)
+
+def _init_child_store(
+    parent: str,
+    index: int,
+):
+    parent_pubkey = makePublicKey(parent)
+    child_pubkey = _child_store_address(parent, index)
+
+    # init child store instruction
+    instruction_data = InitChildStoreInstruction()
+    instruction_data.get("index").object2struct(index)
+
+    transaction_address = client.send_transaction(
+        instruction_data,
+        [
+            makeKeyPair(payerPrivateKey).public_key,
+            # Store authority
+            makeKeyPair(payerPrivateKey).public_key,
+            parent_pubkey,
+            child_pubkey,
+            # No parcel
+            client.program_id,
+            makePublicKey(sysvar_rent),
+            makePublicKey(system_program),
+        ],
+        [
+            makeKeyPair(payerPrivateKey),
+        ],
+        makeKeyPair(payerPrivateKey).public_key
+    )
+
+    return {
+        "transaction_address": transaction_address,
+        "public_key": bs58.encode(child_pubkey.byte_value),
+    }
+
+# Coordinate mapping of a flat world, read from its root store
+COORDINATE_MAPPING_POSITIVE = 0
+COORDINATE_MAPPING_CENTERED = 1
+
+def _store_path_index(x: int, y: int, z: int, depth: int, world_depth: int, mapping: int = COORDINATE_MAPPING_CENTERED):
+    # Centered worlds extend to both sides of the origin, older ones start at it
+    if mapping == COORDINATE_MAPPING_CENTERED:
+        x += 1 << (2 * world_depth - 1)
+        y += 1 << (2 * world_depth - 1)
+        z += 1 << (world_depth - 1)
+    shift = depth - 1
+    return ((x >> (2 * shift)) & 3) | (((y >> (2 * shift)) & 3) << 2) | (((z >> shift) & 1) << 4)
+
+def _parcel_path(world: PublicKey, x: int, y: int, z: int):
+    # Follow the coordinate from the world root down to its parcel store, stop where no store exists yet
+    parcel_depth = client.get_account_data_struct(_world_settings_address(world), WorldSettings).get("parcel_depth").struct2object()
+    path = [world]
+    store = client.get_account_data_struct(world, Store, [8, 0])
+    world_depth = store.get("depth").struct2object()
+    mapping = store.get("mapping").struct2object()
+    parcel = None
+    for level in range(world_depth, parcel_depth, -1):
+        index = _store_path_index(x, y, z, level, world_depth, mapping)
+        if (store.get("occupancy").get(index // 8).struct2object() >> (index % 8)) & 1 == 1:
+            path.append(PublicKey(store.get("pubkeys").getByIndex(index).struct2object()))
+        elif store.get("lazy").struct2object():
+            # Children of lazy stores may exist before they are synced
+            path.append(_child_store_address(bs58.encode(path[-1].byte_value), index))
+            try:
+                store = client.get_account_data_struct(path[-1], Store, [8, 0])
+            except Exception:
+                return path, None
+            continue
+        else:
+            return path, None
+        store = client.get_account_data_struct(path[-1], Store, [8, 0])
+    if store.get("parcel").struct2object() != bs58.encode(bytes(32)):
+        parcel = PublicKey(store.get("parcel").struct2object())
+    return path, parcel
+
+def _create_path(
+    root_store: str,
+    depth: int,
+    x: int,
+    y: int,
+    z: int,
+):
+    # Walk down from the root, existing stores keep their address, missing ones are derived
+    path = [makePublicKey(root_store)]
+    store = client.get_account_data_struct(path[0], Store, [8, 0])
+    mapping = store.get("mapping").struct2object()
+    for level in range(depth, 1, -1):
+        index = _store_path_index(x, y, z, level, depth, mapping)
+        if store is not None and (store.get("occupancy").get(index // 8).struct2object() >> (index % 8)) & 1 == 1:
+            path.append(PublicKey(store.get("pubkeys").getByIndex(index).struct2object()))
+            store = client.get_account_data_struct(path[-1], Store, [8, 0])
+        else:
+            path.append(_child_store_address(bs58.encode(path[-1].byte_value), index))
+            store = None
+
+    # create path instruction
+    instruction_data = CreatePathInstruction()
+    instruction_data.get("xyz").get("x").object2struct(x)
+    instruction_data.get("xyz").get("y").object2struct(y)
+    instruction_data.get("xyz").get("z").object2struct(z)
+
+    transaction_address = client.send_transaction(
+        instruction_data,
+        [
+            makeKeyPair(payerPrivateKey).public_key,
+            # Store authority
+            makeKeyPair(payerPrivateKey).public_key,
+            # No parcel
+            client.program_id,
+            makePublicKey(system_program),
+            *path,
+        ],
+        [
+            makeKeyPair(payerPrivateKey),
+        ],
+        makeKeyPair(payerPrivateKey).public_key
+    )
+
+    return {
+        "transaction_address": transaction_address,
+        "public_key": bs58.encode(path[-1].byte_value),
+    }
+
 def _set_ele_store(
     hi_store: str,
     lo_store: str,
     index: int,
+    path: list[str] = [],
 ):
     hi_store_pubkey = makePublicKey(hi_store)
     lo_store_pubkey = makePublicKey(lo_store)
@@ -147,11 +388,18 @@ def _set_ele_store(
     transaction_address = client.send_transaction(
         instruction_data,
         [
+            makeKeyPair(payerPrivateKey).public_key,
+            # Store authority
+            makeKeyPair(payerPrivateKey).public_key,
+            # Child store authority
             makeKeyPair(payerPrivateKey).public_key,
             hi_store_pubkey,
             lo_store_pubkey,
-            makePublicKey(sysvar_rent),
-            makePublicKey(system_program),
+            # No parcel
+            client.program_id,
+            # Stores from the world root down to the parent of hi_store, only
+            # read when lo_store holds blocks
+            *[makePublicKey(pubkey) for pubkey in path],
         ],
         [
             makeKeyPair(payerPrivateKey),
@@ -169,6 +417,7 @@ def _set_block_store(
     store: str,
     block: str,
     index: int,
+    path: list[str] = [],
 ):
     store_pubkey = makePublicKey(store)
     block_pubkey = makePublicKey(block)
@@ -182,10 +431,14 @@ def _set_block_store(
         [
             makeKeyPair(payerPrivateKey).public_key,
             owner_block.public_key,
+            # Store authority
+            makeKeyPair(payerPrivateKey).public_key,
             store_pubkey,
             block_pubkey,
-            makePublicKey(sysvar_rent),
-            makePublicKey(system_program),
+            # No parcel
+            client.program_id,
+            # Stores from the world root down to the parent of the store
+            *[makePublicKey(pubkey) for pubkey in path],
         ],
         [
             makeKeyPair(payerPrivateKey),
@@ -206,6 +459,7 @@ def _set_block_store(
     _init_store_cal=_init_store_cal,
     _set_ele_store=_set_ele_store,
     _set_block_store=_set_block_store,
+    _init_child_store=_init_child_store,
 )
 class World:
     pass
@@ -217,6 +471,7 @@ class World:
     _init_store_cal=_init_store_cal,
     _set_ele_store=_set_ele_store,
     _set_block_store=_set_block_store,
+    _init_child_store=_init_child_store,
 )
 class Item:
     pass
@@ -229,6 +484,7 @@ class XYZModel(BaseModel):
 
 class InitBlockModel(BaseModel):
     owner_private_key: str
+    world_public_key: str
     xyz: XYZModel
     price: int
     data: list[int] = [0]*512
@@ -253,14 +509,31 @@ async def init_block(data: InitBlockModel):
         ),
         client.program_id)
 
+        # Coordinate claim of the block in the world
+        world_pubkey = makePublicKey(data.world_public_key)
+        block_cell_pubkey = findProgramAddress(createBytesFromArrayBytes(
+            "cell".encode("utf-8"),
+            world_pubkey.byte_value,
+            bytes(instruction_data.get("xyz").serialize())
+        ),
+        client.program_id)
+
+        # Parcel covering the coordinate, only its owner or delegates build there
+        path, parcel_pubkey = _parcel_path(world_pubkey, data.xyz.x, data.xyz.y, data.xyz.z)
+
         transaction_address = client.send_transaction(
             instruction_data,
             [
                 makeKeyPair(payerPrivateKey).public_key,
                 owner_keypair.public_key,
+                world_pubkey,
+                _world_settings_address(world_pubkey),
                 block_pubkey,
+                block_cell_pubkey,
+                parcel_pubkey if parcel_pubkey is not None else client.program_id,
                 makePublicKey(sysvar_rent),
                 makePublicKey(system_program),
+                *path,
             ],
             [
                 makeKeyPair(payerPrivateKey),
@@ -284,6 +557,10 @@ class UpdateBlockModel(BaseModel):
     xyz: XYZModel
     price: int
     data: list[int] = [0]*512
+    expected_revision: int
+    # Pass the block meta, needed to edit a public editable block of
+    # another owner
+    use_block_meta: bool = False
 
 @app.post("/update-block")
 async def update_block(data: UpdateBlockModel):
@@ -297,6 +574,15 @@ async def update_block(data: UpdateBlockModel):
         instruction_data.get("xyz").get("z").object2struct(data.xyz.z)
         instruction_data.get("price").object2struct(data.price)
         instruction_data.get("data").deserialize(data.data)
+        instruction_data.get("expected_revision").object2struct(data.expected_revision)
+
+        block_meta_pubkey = client.program_id
+        if data.use_block_meta:
+            block_meta_pubkey = findProgramAddress(createBytesFromArrayBytes(
+                block_pubkey.byte_value,
+                "meta".encode("utf-8")
+            ),
+            client.program_id)
 
         transaction_address = client.send_transaction(
             instruction_data,
@@ -304,6 +590,9 @@ async def update_block(data: UpdateBlockModel):
                 makeKeyPair(payerPrivateKey).public_key,
                 owner_keypair.public_key,
                 block_pubkey,
+                # No block history account
+                client.program_id,
+                block_meta_pubkey,
                 makePublicKey(sysvar_rent),
                 makePublicKey(system_program),
             ],
@@ -326,6 +615,8 @@ class TradeBlockModel(BaseModel):
     owner_private_key: str
     buyer_private_key: str
     block_public_key: str
+    max_price: int
+    min_price: int = 0
 
 @app.post("/trade-block")
 async def trade_block(data: TradeBlockModel):
@@ -334,8 +625,12 @@ async def trade_block(data: TradeBlockModel):
         buyer_keypair = makeKeyPair(data.buyer_private_key)
         block_pubkey = makePublicKey(data.block_public_key)
 
+        instruction_data = TradeBlockInstruction()
+        instruction_data.get("max_price").object2struct(data.max_price)
+        instruction_data.get("min_price").object2struct(data.min_price)
+
         transaction_address = client.send_transaction(
-            TradeBlockInstruction(),
+            instruction_data,
             [
                 makeKeyPair(payerPrivateKey).public_key,
                 owner_keypair.public_key,
@@ -363,14 +658,14 @@ async def trade_block(data: TradeBlockModel):
 @app.post("/init-world")
 async def init_world():
     def fun():
-        return _init_store_cal(6)
+        return _init_world_cal(6)
 
     return make_response_auto_catch(fun)
 
 @app.post("/init-item")
 async def init_item():
     def fun():
-        return _init_store_cal(3)
+        return _init_world_cal(3)
 
     return make_response_auto_catch(fun)
 
@@ -411,6 +706,16 @@ async def set_block_by_ids_world(
 
     return make_response_auto_catch(fun)
 
+@app.post("/create-path-world")
+async def create_path_world(
+    root_store_public_key: str,
+    xyz: XYZModel,
+):
+    def fun():
+        return _create_path(root_store_public_key, 6, xyz.x, xyz.y, xyz.z)
+
+    return make_response_auto_catch(fun)
+
 # Item
 @app.post("/get-block-by-ids-item")
 async def get_block_by_ids_item(
@@ -6,24 +6,131 @@ use anchor_lang::{prelude::*, solana_program};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use std::{cell::RefCell, rc::Rc};
 
+pub const BLOCK_PERMISSION_EDIT_VOXELS: u8 = 1 << 0;
+pub const BLOCK_PERMISSION_EDIT_PRICE: u8 = 1 << 1;
+pub const BLOCK_PERMISSION_PLACE: u8 = 1 << 2;
+pub const BLOCK_PERMISSIONS_ALL: u8 =
+    BLOCK_PERMISSION_EDIT_VOXELS | BLOCK_PERMISSION_EDIT_PRICE | BLOCK_PERMISSION_PLACE;
+pub const BLOCK_MAX_DELEGATES: usize = 8;
+/// Layout written by this program for `Block::version`. Handlers only accept
+/// blocks of this version; older ones go through `migrate_block` first.
+pub const BLOCK_VERSION: u8 = 1;
+/// Size of blocks created before `Block::version` existed, laid out as
+/// `owner`, `xyz`, `price` and `data`.
+pub const BLOCK_LEGACY_SPACE: usize = 8 // discriminator
+    + 32 // owner
+    + 3 * 8 // xyz
+    + 8 // price
+    + 512; // data
+
 #[account]
 #[derive(Debug)]
 pub struct Block {
+    /// Layout version, `BLOCK_VERSION` once created or migrated.
+    pub version: u8,
     pub owner: Pubkey,
     pub xyz: XYZ,
     pub price: u64,
     pub data: [u8; 512],
+    /// Collaborators allowed to act on the block besides `owner`; a slot is
+    /// free when its permissions are 0.
+    pub delegates: [Pubkey; 8],
+    /// Bit set of `BLOCK_PERMISSION_*` granted to `delegates[i]`.
+    pub delegate_permissions: [u8; 8],
+    /// Lamports per slot asked for renting the block out, 0 when not offered.
+    pub lease_rate: u64,
+    /// Longest lease in slots a renter can buy at `lease_rate`.
+    pub lease_max_slots: u64,
+    /// Renter of the current or last lease.
+    pub renter: Pubkey,
+    /// Slot at which the lease of `renter` ends.
+    pub lease_expiry: u64,
+    /// Set once by `freeze_block`; `xyz` and `data` can never change again.
+    pub frozen: bool,
+    /// Number of updates since `init_block`, price changes included, so
+    /// every write conflicts with a stale `expected_revision`.
+    pub revision: u64,
+    /// Slot of `init_block`.
+    pub created_at: u64,
+    /// Slot of the latest content change.
+    pub updated_at: u64,
+    /// `block_content_hash` of the current `xyz` and `data`.
+    pub content_hash: [u8; 32],
+    /// Rolling hash over the content hashes of every previous revision,
+    /// `history_hash = hash(history_hash || previous content_hash)`.
+    pub history_hash: [u8; 32],
+    /// Recipient of a two-step `transfer_block` waiting for
+    /// `accept_block_transfer`, default when none is pending.
+    pub pending_owner: Pubkey,
+    /// Root store of the world `xyz` is claimed in through a `BlockCell`.
+    pub world: Pubkey,
+    /// Depth 1 store holding the block, default while it is not placed.
+    pub store: Pubkey,
+    /// Slot of the block in `store`.
+    pub store_index: u8,
 }
 
 impl<'info, 'entrypoint> Block {
+    /// Offset of `content_hash` in the account, so a store can read it
+    /// without deserializing the block.
+    pub const CONTENT_HASH_OFFSET: usize = 8 // discriminator
+        + 1 // version
+        + 32 // owner
+        + 3 * 8 // xyz
+        + 8 // price
+        + 512 // data
+        + 32 * BLOCK_MAX_DELEGATES // delegates
+        + BLOCK_MAX_DELEGATES // delegate_permissions
+        + 8 // lease_rate
+        + 8 // lease_max_slots
+        + 32 // renter
+        + 8 // lease_expiry
+        + 1 // frozen
+        + 8 // revision
+        + 8 // created_at
+        + 8; // updated_at
+
+    /// Account size, discriminator included.
+    pub const SPACE: usize = Self::CONTENT_HASH_OFFSET
+        + 32 // content_hash
+        + 32 // history_hash
+        + 32 // pending_owner
+        + 32 // world
+        + 32 // store
+        + 1; // store_index
+
     pub fn load(
         account: &'entrypoint mut Box<Account<'info, Self>>,
         programs_map: &'entrypoint ProgramsMap<'info>,
     ) -> Mutable<LoadedBlock<'info, 'entrypoint>> {
+        // Older layouts are shorter, a version byte alone could be any
+        // byte of them
+        if !(account.version == BLOCK_VERSION
+            && account.to_account_info().data_len() == Self::SPACE)
+        {
+            panic!("Block must be migrated with migrate_block");
+        }
+
         let owner = account.owner.clone();
         let xyz = Mutable::new(account.xyz.clone());
         let price = account.price;
         let data = Mutable::new(account.data.clone());
+        let delegates = Mutable::new(account.delegates.clone());
+        let delegate_permissions = Mutable::new(account.delegate_permissions.clone());
+        let lease_rate = account.lease_rate;
+        let lease_max_slots = account.lease_max_slots;
+        let renter = account.renter.clone();
+        let lease_expiry = account.lease_expiry;
+        let frozen = account.frozen;
+        let revision = account.revision;
+        let created_at = account.created_at;
+        let updated_at = account.updated_at;
+        let content_hash = Mutable::new(account.content_hash.clone());
+        let history_hash = Mutable::new(account.history_hash.clone());
+        let pending_owner = account.pending_owner.clone();
+        let world = account.world.clone();
+        let store = account.store.clone();
+        let store_index = account.store_index;
 
         Mutable::new(LoadedBlock {
             __account__: account,
@@ -32,6 +139,22 @@ impl<'info, 'entrypoint> Block {
             xyz,
             price,
             data,
+            delegates,
+            delegate_permissions,
+            lease_rate,
+            lease_max_slots,
+            renter,
+            lease_expiry,
+            frozen,
+            revision,
+            created_at,
+            updated_at,
+            content_hash,
+            history_hash,
+            pending_owner,
+            world,
+            store,
+            store_index,
         })
     }
 
@@ -52,6 +175,70 @@ impl<'info, 'entrypoint> Block {
         let data = loaded.data.borrow().clone();
 
         loaded.__account__.data = data;
+
+        let delegates = loaded.delegates.borrow().clone();
+
+        loaded.__account__.delegates = delegates;
+
+        let delegate_permissions = loaded.delegate_permissions.borrow().clone();
+
+        loaded.__account__.delegate_permissions = delegate_permissions;
+
+        let lease_rate = loaded.lease_rate;
+
+        loaded.__account__.lease_rate = lease_rate;
+
+        let lease_max_slots = loaded.lease_max_slots;
+
+        loaded.__account__.lease_max_slots = lease_max_slots;
+
+        let renter = loaded.renter.clone();
+
+        loaded.__account__.renter = renter;
+
+        let lease_expiry = loaded.lease_expiry;
+
+        loaded.__account__.lease_expiry = lease_expiry;
+
+        let frozen = loaded.frozen;
+
+        loaded.__account__.frozen = frozen;
+
+        let revision = loaded.revision;
+
+        loaded.__account__.revision = revision;
+
+        let created_at = loaded.created_at;
+
+        loaded.__account__.created_at = created_at;
+
+        let updated_at = loaded.updated_at;
+
+        loaded.__account__.updated_at = updated_at;
+
+        let content_hash = loaded.content_hash.borrow().clone();
+
+        loaded.__account__.content_hash = content_hash;
+
+        let history_hash = loaded.history_hash.borrow().clone();
+
+        loaded.__account__.history_hash = history_hash;
+
+        let pending_owner = loaded.pending_owner.clone();
+
+        loaded.__account__.pending_owner = pending_owner;
+
+        let world = loaded.world.clone();
+
+        loaded.__account__.world = world;
+
+        let store = loaded.store.clone();
+
+        loaded.__account__.store = store;
+
+        let store_index = loaded.store_index;
+
+        loaded.__account__.store_index = store_index;
     }
 }
 
@@ -63,240 +250,4351 @@ pub struct LoadedBlock<'info, 'entrypoint> {
     pub xyz: Mutable<XYZ>,
     pub price: u64,
     pub data: Mutable<[u8; 512]>,
+    pub delegates: Mutable<[Pubkey; 8]>,
+    pub delegate_permissions: Mutable<[u8; 8]>,
+    pub lease_rate: u64,
+    pub lease_max_slots: u64,
+    pub renter: Pubkey,
+    pub lease_expiry: u64,
+    pub frozen: bool,
+    pub revision: u64,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub content_hash: Mutable<[u8; 32]>,
+    pub history_hash: Mutable<[u8; 32]>,
+    pub pending_owner: Pubkey,
+    pub world: Pubkey,
+    pub store: Pubkey,
+    pub store_index: u8,
 }
 
+/// Claim of the cell `xyz` of `world` by `block`, kept at
+/// `["cell", world, x, y, z]` so no two blocks of a world share
+/// coordinates. Created by `init_block`, released by `move_block` and
+/// `close_block`.
 #[account]
 #[derive(Debug)]
-pub struct HotaStore {
-    pub depth: u8,
-    pub pubkeys: [Pubkey; 32],
-    pub status: [u8; 32],
+pub struct BlockCell {
+    pub world: Pubkey,
+    pub xyz: XYZ,
+    pub block: Pubkey,
 }
 
-impl<'info, 'entrypoint> HotaStore {
+impl<'info, 'entrypoint> BlockCell {
     pub fn load(
         account: &'entrypoint mut Box<Account<'info, Self>>,
         programs_map: &'entrypoint ProgramsMap<'info>,
-    ) -> Mutable<LoadedHotaStore<'info, 'entrypoint>> {
-        let depth = account.depth;
-        let pubkeys = Mutable::new(account.pubkeys.clone());
-        let status = Mutable::new(account.status.clone());
+    ) -> Mutable<LoadedBlockCell<'info, 'entrypoint>> {
+        let world = account.world.clone();
+        let xyz = Mutable::new(account.xyz.clone());
+        let block = account.block.clone();
 
-        Mutable::new(LoadedHotaStore {
+        Mutable::new(LoadedBlockCell {
             __account__: account,
             __programs__: programs_map,
-            depth,
-            pubkeys,
-            status,
+            world,
+            xyz,
+            block,
         })
     }
 
-    pub fn store(loaded: Mutable<LoadedHotaStore>) {
+    pub fn store(loaded: Mutable<LoadedBlockCell>) {
         let mut loaded = loaded.borrow_mut();
-        let depth = loaded.depth;
+        let world = loaded.world.clone();
 
-        loaded.__account__.depth = depth;
+        loaded.__account__.world = world;
 
-        let pubkeys = loaded.pubkeys.borrow().clone();
+        let xyz = loaded.xyz.borrow().clone();
 
-        loaded.__account__.pubkeys = pubkeys;
+        loaded.__account__.xyz = xyz;
 
-        let status = loaded.status.borrow().clone();
+        let block = loaded.block.clone();
 
-        loaded.__account__.status = status;
+        loaded.__account__.block = block;
     }
 }
 
 #[derive(Debug)]
-pub struct LoadedHotaStore<'info, 'entrypoint> {
-    pub __account__: &'entrypoint mut Box<Account<'info, HotaStore>>,
+pub struct LoadedBlockCell<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, BlockCell>>,
     pub __programs__: &'entrypoint ProgramsMap<'info>,
-    pub depth: u8,
-    pub pubkeys: Mutable<[Pubkey; 32]>,
-    pub status: Mutable<[u8; 32]>,
+    pub world: Pubkey,
+    pub xyz: Mutable<XYZ>,
+    pub block: Pubkey,
 }
 
-#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug, Default)]
-pub struct XYZ {
-    pub x: u64,
-    pub y: u64,
-    pub z: u64,
+pub const BLOCK_HISTORY_LEN: usize = 4;
+
+/// Content of a `Block` as it was at `revision`.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct BlockRevision {
+    pub revision: u64,
+    pub updated_at: u64,
+    pub xyz: XYZ,
+    pub data: [u8; 512],
 }
 
-pub fn init_block_handler<'info>(
-    mut payer: SeahorseSigner<'info, '_>,
-    mut owner: SeahorseSigner<'info, '_>,
-    mut block: Empty<Mutable<LoadedBlock<'info, '_>>>,
-    mut xyz: XYZ,
-    mut price: u64,
-    mut data: [u8; 512],
-    mut seed_random: u128,
-) -> () {
-    let mut block = block.account.clone();
+/// Ring buffer at `[block, "history"]` with the content replaced by the
+/// last `BLOCK_HISTORY_LEN` revisions of `block`.
+#[account]
+#[derive(Debug)]
+pub struct BlockHistory {
+    pub block: Pubkey,
+    pub head: u8,
+    pub len: u8,
+    pub entries: [BlockRevision; 4],
+}
 
-    assign!(block.borrow_mut().owner, owner.key());
+impl<'info, 'entrypoint> BlockHistory {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedBlockHistory<'info, 'entrypoint>> {
+        let block = account.block.clone();
+        let head = account.head;
+        let len = account.len;
+        let entries = Mutable::new(account.entries.clone());
 
-    assign!(block.borrow_mut().xyz, Mutable::<XYZ>::new(xyz));
+        Mutable::new(LoadedBlockHistory {
+            __account__: account,
+            __programs__: programs_map,
+            block,
+            head,
+            len,
+            entries,
+        })
+    }
 
-    assign!(block.borrow_mut().price, price);
+    pub fn store(loaded: Mutable<LoadedBlockHistory>) {
+        let mut loaded = loaded.borrow_mut();
+        let block = loaded.block.clone();
 
-    assign!(block.borrow_mut().data, Mutable::<[u8; 512]>::new(data));
-}
+        loaded.__account__.block = block;
 
-pub fn init_store_handler<'info>(
-    mut payer: SeahorseSigner<'info, '_>,
-    mut hota_store: Empty<Mutable<LoadedHotaStore<'info, '_>>>,
-    mut depth: u8,
-    mut seed_random: u128,
-) -> () {
-    if !(depth > 0) {
-        panic!("depth of store much > 0");
-    }
+        let head = loaded.head;
 
-    let mut hota_store = hota_store.account.clone();
+        loaded.__account__.head = head;
 
-    assign!(hota_store.borrow_mut().depth, depth);
-}
+        let len = loaded.len;
 
-pub fn set_block_store_handler<'info>(
-    mut payer: SeahorseSigner<'info, '_>,
-    mut owner_block: SeahorseSigner<'info, '_>,
-    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
-    mut block: Mutable<LoadedBlock<'info, '_>>,
-    mut index: u8,
-) -> () {
-    if !(index < 32) {
-        panic!("Index much be < 32");
-    }
+        loaded.__account__.len = len;
 
-    if !(owner_block.key() == block.borrow().owner) {
-        panic!("Owner of block much be signer");
-    }
+        let entries = loaded.entries.borrow().clone();
 
-    if !(hota_store.borrow().depth == 1) {
-        panic!("Store much have depth = 1");
+        loaded.__account__.entries = entries;
     }
+}
 
-    if !(hota_store.borrow().status.borrow()[hota_store
-        .borrow()
-        .status
-        .wrapped_index((index as i128) as i128)]
-        == 0)
-    {
-        panic!("Store with given index not available");
-    }
+#[derive(Debug)]
+pub struct LoadedBlockHistory<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, BlockHistory>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub block: Pubkey,
+    pub head: u8,
+    pub len: u8,
+    pub entries: Mutable<[BlockRevision; 4]>,
+}
 
-    index_assign!(
-        hota_store.borrow_mut().pubkeys.borrow_mut(),
-        hota_store
-            .borrow_mut()
-            .pubkeys
-            .wrapped_index((index as i128) as i128),
-        block.borrow().__account__.key()
-    );
+/// `close_block` is rejected; once set the flag can not be cleared.
+pub const BLOCK_META_FLAG_INDESTRUCTIBLE: u8 = 1 << 0;
+/// Any signer may edit the voxels through `update_block` while the block is
+/// not leased; coordinates and price stay with the owner and delegates.
+pub const BLOCK_META_FLAG_PUBLIC_EDITABLE: u8 = 1 << 1;
+/// Clients should not render the block. The program does not act on it, the
+/// block stays readable on chain.
+pub const BLOCK_META_FLAG_HIDDEN: u8 = 1 << 2;
+pub const BLOCK_META_FLAGS_ALL: u8 =
+    BLOCK_META_FLAG_INDESTRUCTIBLE | BLOCK_META_FLAG_PUBLIC_EDITABLE | BLOCK_META_FLAG_HIDDEN;
+pub const BLOCK_META_MAX_LIGHT: u8 = 15;
 
-    index_assign!(
-        hota_store.borrow_mut().status.borrow_mut(),
-        hota_store
-            .borrow_mut()
-            .status
-            .wrapped_index((index as i128) as i128),
-        1
-    );
+/// Gameplay attributes of a `Block`, kept in their own account at
+/// `[block, "meta"]` so the voxel layout of `Block` stays unchanged.
+///
+/// Layout after the 8 byte discriminator (195 bytes, no padding):
+///
+/// | offset | size | field                                             |
+/// |--------|------|---------------------------------------------------|
+/// | 0      | 32   | `block`: the `Block` these attributes belong to   |
+/// | 32     | 1    | `biome`: biome id, interpreted by the game client |
+/// | 33     | 1    | `light`: light emission level, 0..=15             |
+/// | 34     | 1    | `flags`: bit set of `BLOCK_META_FLAG_*`           |
+/// | 35     | 32   | `name`: UTF-8 display name, zero padded           |
+/// | 67     | 128  | `uri`: UTF-8 content URI, zero padded             |
+#[account]
+#[derive(Debug)]
+pub struct BlockMeta {
+    pub block: Pubkey,
+    pub biome: u8,
+    pub light: u8,
+    pub flags: u8,
+    pub name: [u8; 32],
+    pub uri: [u8; 128],
 }
 
-pub fn set_ele_store_handler<'info>(
-    mut payer: SeahorseSigner<'info, '_>,
-    mut hi_store: Mutable<LoadedHotaStore<'info, '_>>,
-    mut lo_store: Mutable<LoadedHotaStore<'info, '_>>,
-    mut index: u8,
-) -> () {
-    if !(index < 32) {
-        panic!("Index much be < 32");
-    }
+impl<'info, 'entrypoint> BlockMeta {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedBlockMeta<'info, 'entrypoint>> {
+        let block = account.block.clone();
+        let biome = account.biome;
+        let light = account.light;
+        let flags = account.flags;
+        let name = Mutable::new(account.name.clone());
+        let uri = Mutable::new(account.uri.clone());
 
-    if !(lo_store.borrow().depth > 0) {
-        panic!("low node much have depth > 0");
+        Mutable::new(LoadedBlockMeta {
+            __account__: account,
+            __programs__: programs_map,
+            block,
+            biome,
+            light,
+            flags,
+            name,
+            uri,
+        })
     }
 
-    if !(hi_store.borrow().depth == (lo_store.borrow().depth + 1)) {
-        panic!("high node much have depth = low node depth + 1");
-    }
+    pub fn store(loaded: Mutable<LoadedBlockMeta>) {
+        let mut loaded = loaded.borrow_mut();
+        let block = loaded.block.clone();
 
-    if !(hi_store.borrow().status.borrow()[hi_store
-        .borrow()
-        .status
-        .wrapped_index((index as i128) as i128)]
-        == 0)
-    {
-        panic!("High node with given index not available");
+        loaded.__account__.block = block;
+
+        let biome = loaded.biome;
+
+        loaded.__account__.biome = biome;
+
+        let light = loaded.light;
+
+        loaded.__account__.light = light;
+
+        let flags = loaded.flags;
+
+        loaded.__account__.flags = flags;
+
+        let name = loaded.name.borrow().clone();
+
+        loaded.__account__.name = name;
+
+        let uri = loaded.uri.borrow().clone();
+
+        loaded.__account__.uri = uri;
     }
+}
 
-    index_assign!(
-        hi_store.borrow_mut().pubkeys.borrow_mut(),
-        hi_store
-            .borrow_mut()
-            .pubkeys
-            .wrapped_index((index as i128) as i128),
-        lo_store.borrow().__account__.key()
-    );
+#[derive(Debug)]
+pub struct LoadedBlockMeta<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, BlockMeta>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub block: Pubkey,
+    pub biome: u8,
+    pub light: u8,
+    pub flags: u8,
+    pub name: Mutable<[u8; 32]>,
+    pub uri: Mutable<[u8; 128]>,
+}
 
-    index_assign!(
-        hi_store.borrow_mut().status.borrow_mut(),
-        hi_store
-            .borrow_mut()
-            .status
-            .wrapped_index((index as i128) as i128),
-        1
-    );
+/// Slots are read and written in place in the account data, so even the
+/// widest stores are never copied onto the heap.
+pub const STORE_MAX_FAN_OUT: u16 = 256;
+/// Bytes of a per-slot bitmap such as `HotaStore::occupancy`.
+pub const STORE_BITMAP_LEN: usize = STORE_MAX_FAN_OUT as usize / 8;
+pub const STORE_MAX_DELEGATES: usize = 4;
+/// Layout written by this program for `HotaStore::version`. Handlers only
+/// accept stores of this version; older ones go through `migrate_store` first.
+pub const STORE_VERSION: u8 = 1;
+/// Size of stores created before `HotaStore::version` existed, laid out as
+/// `depth`, `STORE_LEGACY_FAN_OUT` child pubkeys and as many status bytes,
+/// non-zero for occupied slots.
+pub const STORE_LEGACY_SPACE: usize = 8 // discriminator
+    + 1 // depth
+    + 32 * STORE_LEGACY_FAN_OUT as usize // pubkeys
+    + STORE_LEGACY_FAN_OUT as usize; // status
+/// Slots of every store created before `HotaStore::version` existed.
+pub const STORE_LEGACY_FAN_OUT: u16 = 32;
+
+/// Slots carry no spatial meaning.
+pub const STORE_KIND_FLAT: u8 = 0;
+/// 8 slots, one per octant, `dx | dy << 1 | dz << 2`.
+pub const STORE_KIND_OCTREE: u8 = 1;
+/// 32 slots in a 4 x 4 x 2 grid, `dx | dy << 2 | dz << 4`.
+pub const STORE_KIND_SPATIAL_32: u8 = 2;
+
+/// Flat worlds from before signed coordinates, starting at the origin and
+/// extending along the positive axes only.
+pub const COORDINATE_MAPPING_POSITIVE: u8 = 0;
+/// Flat worlds centered on the origin, extending equally to both sides.
+pub const COORDINATE_MAPPING_CENTERED: u8 = 1;
+
+/// Root stores live at `["store", depth, seed_random]`. Stores created with
+/// `init_child_store` live at `["store", parent, index]`, so the address of
+/// every node on a path can be derived from the root alone.
+///
+/// The fields below are followed, at `SLOTS_OFFSET`, by the entries of every
+/// slot: `lod_materials`, the dominant material of the child (0 for air), and
+/// `pubkeys`, the child itself, each laid out as a Borsh `Vec` of `fan_out`
+/// entries. They are only accessed in place through `store_child` and
+/// `set_store_child`.
+#[account]
+#[derive(Debug)]
+pub struct HotaStore {
+    /// Layout version, `STORE_VERSION` once created or migrated.
+    pub version: u8,
+    pub depth: u8,
+    /// Number of child slots, 1..=`STORE_MAX_FAN_OUT`, fixed by `init_store`.
+    pub fan_out: u16,
+    /// Number of occupied slots.
+    pub child_count: u16,
+    /// Bit `i % 8` of byte `i / 8` is set when slot `i` holds a child.
+    pub occupancy: [u8; STORE_BITMAP_LEN],
+    /// Signer required, besides `delegates`, to change the slots of the store.
+    pub authority: Pubkey,
+    /// Accounts allowed to change the slots on behalf of `authority`; a
+    /// slot is free when it holds the default pubkey.
+    pub delegates: [Pubkey; 4],
+    /// `Parcel` the store lies in, default outside of any parcel. Stores
+    /// linked below a parcel store inherit it.
+    pub parcel: Pubkey,
+    /// Root of the world whose blocks the subtree holds, default while no
+    /// block has been placed below the store; a world root holds its own
+    /// address from `init_world`. Recorded along the path when a block is
+    /// placed and inherited by child stores; subtrees are only linked below
+    /// stores of their own world.
+    pub world: Pubkey,
+    /// Asking price for `sell_store`, 0 when not for sale.
+    pub price: u64,
+    /// Store holding this one, default for roots and detached subtrees.
+    pub parent: Pubkey,
+    /// Slot of the store in `parent`.
+    pub parent_index: u8,
+    /// Payer of the store rent, refunded by `close_store`.
+    pub creator: Pubkey,
+    /// One of `STORE_KIND_*`.
+    pub kind: u8,
+    /// Lowest block coordinate covered by a spatial store.
+    pub origin: XYZ,
+    /// Extent of a spatial store in blocks along each axis.
+    pub edge: XYZ,
+    /// One of `COORDINATE_MAPPING_*`, how `create_path` lays out the
+    /// coordinates of a flat world rooted at this store. Only read on world
+    /// roots; child stores inherit it.
+    pub mapping: u8,
+    /// Set once by `set_store_lazy`. Slots of a lazy store are claimed
+    /// without writing it, through `StoreSlot` accounts for blocks and
+    /// `["store", store, index]` addresses for child stores, and folded into
+    /// `occupancy`, `child_count` and the slot children later by
+    /// `sync_store_slots`.
+    pub lazy: bool,
+    /// Set on stores created at `["store", parent, index]` and on stores
+    /// holding such a child. A derived store only accepts children at their
+    /// derived address, so clients can compute every address on a path.
+    pub derived: bool,
+    /// Previous authority whose stores and blocks below this one still have
+    /// to be handed to `authority` by `cascade_store_transfer`, default when
+    /// nothing is pending.
+    pub transfer_from: Pubkey,
+    /// Whether the pending hand-down includes blocks.
+    pub transfer_blocks: bool,
+    /// First slot not yet handed down.
+    pub transfer_cursor: u8,
+    /// Root of the binary Merkle tree over the slots, `merkle_root` of
+    /// `chunk_hashes`, all zero for an empty store. Set by
+    /// `refresh_store_hash`; refreshing bottom-up makes the root hash commit
+    /// to the whole world.
+    pub content_hash: [u8; 32],
+    /// Root of the subtree over the 8 slots of every chunk, each slot a
+    /// `store_slot_leaf`, all zero for a chunk without children.
+    pub chunk_hashes: [[u8; 32]; STORE_BITMAP_LEN],
+    /// Slot of the last `refresh_store_hash`.
+    pub hashed_at: u64,
+    /// Bit `i % 8` of byte `i / 8` is set when the child in slot `i` holds
+    /// any voxel, as of the last `refresh_store_lod`.
+    pub lod_solid: [u8; STORE_BITMAP_LEN],
 }
 
-pub fn trade_block_handler<'info>(
-    mut payer: SeahorseSigner<'info, '_>,
-    mut old_onwer: SeahorseSigner<'info, '_>,
-    mut new_owner: SeahorseSigner<'info, '_>,
-    mut block: Mutable<LoadedBlock<'info, '_>>,
-) -> () {
-    if !(block.borrow().price > 0) {
-        panic!("Block not for sale");
-    }
+impl<'info, 'entrypoint> HotaStore {
+    /// Offset of `content_hash` in the account, so a parent can read it
+    /// without deserializing the store.
+    pub const CONTENT_HASH_OFFSET: usize = 8 // discriminator
+        + 1 // version
+        + 1 // depth
+        + 2 // fan_out
+        + 2 // child_count
+        + STORE_BITMAP_LEN // occupancy
+        + 32 // authority
+        + 32 * STORE_MAX_DELEGATES // delegates
+        + 32 // parcel
+        + 32 // world
+        + 8 // price
+        + 32 // parent
+        + 1 // parent_index
+        + 32 // creator
+        + 1 // kind
+        + 3 * 8 // origin
+        + 3 * 8 // edge
+        + 1 // mapping
+        + 1 // lazy
+        + 1 // derived
+        + 32 // transfer_from
+        + 1 // transfer_blocks
+        + 1; // transfer_cursor
 
-    if !(old_onwer.key() == block.borrow().owner) {
-        panic!("Owner of block much be signer");
+    /// Offset of the slot entries, right after the fixed fields.
+    pub const SLOTS_OFFSET: usize = Self::CONTENT_HASH_OFFSET
+        + 32 // content_hash
+        + 32 * STORE_BITMAP_LEN // chunk_hashes
+        + 8 // hashed_at
+        + STORE_BITMAP_LEN; // lod_solid
+
+    /// Account size, discriminator included, of a store with `fan_out` slots.
+    pub fn space(fan_out: u16) -> usize {
+        let fan_out = fan_out as usize;
+        let pubkey = std::mem::size_of::<Pubkey>();
+
+        return Self::SLOTS_OFFSET
+            + 4 + fan_out // lod_materials
+            + 4 + pubkey * fan_out; // pubkeys
     }
 
-    solana_program::program::invoke(
-        &solana_program::system_instruction::transfer(
-            &new_owner.key(),
-            &old_onwer.clone().key(),
-            block.borrow().price.clone(),
-        ),
-        &[
-            new_owner.to_account_info(),
-            old_onwer.clone().to_account_info(),
-            new_owner.programs.get("system_program").clone(),
-        ],
-    )
-    .unwrap();
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedHotaStore<'info, 'entrypoint>> {
+        if !(account.version == STORE_VERSION
+            && account.to_account_info().data_len() == Self::space(account.fan_out))
+        {
+            panic!("Store must be migrated with migrate_store");
+        }
 
-    assign!(block.borrow_mut().owner, new_owner.key());
-}
+        let depth = account.depth;
+        let fan_out = account.fan_out;
+        let child_count = account.child_count;
+        let occupancy = Mutable::new(account.occupancy.clone());
+        let authority = account.authority.clone();
+        let delegates = Mutable::new(account.delegates.clone());
+        let parcel = account.parcel.clone();
+        let world = account.world.clone();
+        let price = account.price;
+        let parent = account.parent.clone();
+        let parent_index = account.parent_index;
+        let creator = account.creator.clone();
+        let kind = account.kind;
+        let origin = Mutable::new(account.origin.clone());
+        let edge = Mutable::new(account.edge.clone());
+        let mapping = account.mapping;
+        let lazy = account.lazy.clone();
+        let derived = account.derived.clone();
+        let transfer_from = account.transfer_from.clone();
+        let transfer_blocks = account.transfer_blocks.clone();
+        let transfer_cursor = account.transfer_cursor;
+        let content_hash = Mutable::new(account.content_hash.clone());
+        let chunk_hashes = Mutable::new(account.chunk_hashes.clone());
+        let hashed_at = account.hashed_at;
+        let lod_solid = Mutable::new(account.lod_solid.clone());
 
-pub fn update_block_handler<'info>(
-    mut payer: SeahorseSigner<'info, '_>,
-    mut owner_block: SeahorseSigner<'info, '_>,
-    mut block: Mutable<LoadedBlock<'info, '_>>,
-    mut xyz: XYZ,
-    mut price: u64,
-    mut data: [u8; 512],
-) -> () {
-    if !(owner_block.key() == block.borrow().owner) {
-        panic!("Owner of block much be signer");
+        Mutable::new(LoadedHotaStore {
+            __account__: account,
+            __programs__: programs_map,
+            depth,
+            fan_out,
+            child_count,
+            occupancy,
+            authority,
+            delegates,
+            parcel,
+            world,
+            price,
+            parent,
+            parent_index,
+            creator,
+            kind,
+            origin,
+            edge,
+            mapping,
+            lazy,
+            derived,
+            transfer_from,
+            transfer_blocks,
+            transfer_cursor,
+            content_hash,
+            chunk_hashes,
+            hashed_at,
+            lod_solid,
+        })
     }
 
-    assign!(block.borrow_mut().xyz, Mutable::<XYZ>::new(xyz));
+    pub fn store(loaded: Mutable<LoadedHotaStore>) {
+        let mut loaded = loaded.borrow_mut();
+        let depth = loaded.depth;
 
-    assign!(block.borrow_mut().price, price);
+        loaded.__account__.depth = depth;
 
-    assign!(block.borrow_mut().data, Mutable::<[u8; 512]>::new(data));
+        let fan_out = loaded.fan_out;
+
+        loaded.__account__.fan_out = fan_out;
+
+        let child_count = loaded.child_count;
+
+        loaded.__account__.child_count = child_count;
+
+        let occupancy = loaded.occupancy.borrow().clone();
+
+        loaded.__account__.occupancy = occupancy;
+
+        let authority = loaded.authority.clone();
+
+        loaded.__account__.authority = authority;
+
+        let delegates = loaded.delegates.borrow().clone();
+
+        loaded.__account__.delegates = delegates;
+
+        let parcel = loaded.parcel.clone();
+
+        loaded.__account__.parcel = parcel;
+
+        let world = loaded.world.clone();
+
+        loaded.__account__.world = world;
+
+        let price = loaded.price;
+
+        loaded.__account__.price = price;
+
+        let parent = loaded.parent.clone();
+
+        loaded.__account__.parent = parent;
+
+        let parent_index = loaded.parent_index;
+
+        loaded.__account__.parent_index = parent_index;
+
+        let creator = loaded.creator.clone();
+
+        loaded.__account__.creator = creator;
+
+        let kind = loaded.kind;
+
+        loaded.__account__.kind = kind;
+
+        let origin = loaded.origin.borrow().clone();
+
+        loaded.__account__.origin = origin;
+
+        let edge = loaded.edge.borrow().clone();
+
+        loaded.__account__.edge = edge;
+
+        let mapping = loaded.mapping;
+
+        loaded.__account__.mapping = mapping;
+
+        let lazy = loaded.lazy.clone();
+
+        loaded.__account__.lazy = lazy;
+
+        let derived = loaded.derived.clone();
+
+        loaded.__account__.derived = derived;
+
+        let transfer_from = loaded.transfer_from.clone();
+
+        loaded.__account__.transfer_from = transfer_from;
+
+        let transfer_blocks = loaded.transfer_blocks.clone();
+
+        loaded.__account__.transfer_blocks = transfer_blocks;
+
+        let transfer_cursor = loaded.transfer_cursor;
+
+        loaded.__account__.transfer_cursor = transfer_cursor;
+
+        let content_hash = loaded.content_hash.borrow().clone();
+
+        loaded.__account__.content_hash = content_hash;
+
+        let chunk_hashes = loaded.chunk_hashes.borrow().clone();
+
+        loaded.__account__.chunk_hashes = chunk_hashes;
+
+        let hashed_at = loaded.hashed_at;
+
+        loaded.__account__.hashed_at = hashed_at;
+
+        let lod_solid = loaded.lod_solid.borrow().clone();
+
+        loaded.__account__.lod_solid = lod_solid;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedHotaStore<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, HotaStore>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub depth: u8,
+    pub fan_out: u16,
+    pub child_count: u16,
+    pub occupancy: Mutable<[u8; STORE_BITMAP_LEN]>,
+    pub authority: Pubkey,
+    pub delegates: Mutable<[Pubkey; 4]>,
+    pub parcel: Pubkey,
+    pub world: Pubkey,
+    pub price: u64,
+    pub parent: Pubkey,
+    pub parent_index: u8,
+    pub creator: Pubkey,
+    pub kind: u8,
+    pub origin: Mutable<XYZ>,
+    pub edge: Mutable<XYZ>,
+    pub mapping: u8,
+    pub lazy: bool,
+    pub derived: bool,
+    pub transfer_from: Pubkey,
+    pub transfer_blocks: bool,
+    pub transfer_cursor: u8,
+    pub content_hash: Mutable<[u8; 32]>,
+    pub chunk_hashes: Mutable<[[u8; 32]; STORE_BITMAP_LEN]>,
+    pub hashed_at: u64,
+    pub lod_solid: Mutable<[u8; STORE_BITMAP_LEN]>,
+}
+
+/// Block claimed for slot `index` of the lazy depth 1 `store`, kept at
+/// `[store, "slot", index]` so a slot can only be claimed once.
+#[account]
+#[derive(Debug)]
+pub struct StoreSlot {
+    pub store: Pubkey,
+    pub index: u8,
+    pub child: Pubkey,
+}
+
+impl<'info, 'entrypoint> StoreSlot {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedStoreSlot<'info, 'entrypoint>> {
+        let store = account.store.clone();
+        let index = account.index;
+        let child = account.child.clone();
+
+        Mutable::new(LoadedStoreSlot {
+            __account__: account,
+            __programs__: programs_map,
+            store,
+            index,
+            child,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedStoreSlot>) {
+        let mut loaded = loaded.borrow_mut();
+        let store = loaded.store.clone();
+
+        loaded.__account__.store = store;
+
+        let index = loaded.index;
+
+        loaded.__account__.index = index;
+
+        let child = loaded.child.clone();
+
+        loaded.__account__.child = child;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedStoreSlot<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, StoreSlot>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub store: Pubkey,
+    pub index: u8,
+    pub child: Pubkey,
+}
+
+/// Land sale settings of the world rooted at `root`, kept at
+/// `["world", root]`. Blocks can only be created in stores that have one.
+#[account]
+#[derive(Debug)]
+pub struct World {
+    pub root: Pubkey,
+    pub authority: Pubkey,
+    /// Receives the fee of every `claim_parcel`.
+    pub treasury: Pubkey,
+    pub parcel_fee: u64,
+    /// Depth of the stores that can be claimed as parcels.
+    pub parcel_depth: u8,
+}
+
+impl<'info, 'entrypoint> World {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedWorld<'info, 'entrypoint>> {
+        let root = account.root.clone();
+        let authority = account.authority.clone();
+        let treasury = account.treasury.clone();
+        let parcel_fee = account.parcel_fee;
+        let parcel_depth = account.parcel_depth;
+
+        Mutable::new(LoadedWorld {
+            __account__: account,
+            __programs__: programs_map,
+            root,
+            authority,
+            treasury,
+            parcel_fee,
+            parcel_depth,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedWorld>) {
+        let mut loaded = loaded.borrow_mut();
+        let root = loaded.root.clone();
+
+        loaded.__account__.root = root;
+
+        let authority = loaded.authority.clone();
+
+        loaded.__account__.authority = authority;
+
+        let treasury = loaded.treasury.clone();
+
+        loaded.__account__.treasury = treasury;
+
+        let parcel_fee = loaded.parcel_fee;
+
+        loaded.__account__.parcel_fee = parcel_fee;
+
+        let parcel_depth = loaded.parcel_depth;
+
+        loaded.__account__.parcel_depth = parcel_depth;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedWorld<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, World>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub root: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub parcel_fee: u64,
+    pub parcel_depth: u8,
+}
+
+pub const PARCEL_MAX_DELEGATES: usize = 4;
+
+/// Exclusive build rights over the subtree of `store`, kept at
+/// `["parcel", store]`. Every store of the subtree carries the parcel, so
+/// placing a block anywhere in it, or linking stores into it, needs
+/// `owner` or one of `delegates`.
+#[account]
+#[derive(Debug)]
+pub struct Parcel {
+    pub world: Pubkey,
+    pub store: Pubkey,
+    pub owner: Pubkey,
+    /// Asking price for `trade_parcel`, 0 when not for sale.
+    pub price: u64,
+    pub delegates: [Pubkey; 4],
+}
+
+impl<'info, 'entrypoint> Parcel {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedParcel<'info, 'entrypoint>> {
+        let world = account.world.clone();
+        let store = account.store.clone();
+        let owner = account.owner.clone();
+        let price = account.price;
+        let delegates = Mutable::new(account.delegates.clone());
+
+        Mutable::new(LoadedParcel {
+            __account__: account,
+            __programs__: programs_map,
+            world,
+            store,
+            owner,
+            price,
+            delegates,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedParcel>) {
+        let mut loaded = loaded.borrow_mut();
+        let world = loaded.world.clone();
+
+        loaded.__account__.world = world;
+
+        let store = loaded.store.clone();
+
+        loaded.__account__.store = store;
+
+        let owner = loaded.owner.clone();
+
+        loaded.__account__.owner = owner;
+
+        let price = loaded.price;
+
+        loaded.__account__.price = price;
+
+        let delegates = loaded.delegates.borrow().clone();
+
+        loaded.__account__.delegates = delegates;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedParcel<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, Parcel>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub world: Pubkey,
+    pub store: Pubkey,
+    pub owner: Pubkey,
+    pub price: u64,
+    pub delegates: Mutable<[Pubkey; 4]>,
+}
+
+/// Panics unless `signer` may change the slots of `store`: the owner or a
+/// delegate of its parcel when it lies in one, its authority or a delegate
+/// otherwise.
+pub fn check_store_editor<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    parcel: &Option<Mutable<LoadedParcel<'info, '_>>>,
+    signer: Pubkey,
+) -> () {
+    if store.borrow().parcel == Pubkey::default() {
+        check_store_authority(store, signer);
+
+        return;
+    }
+
+    check_parcel_editor(store.borrow().parcel, parcel, signer);
+}
+
+/// `check_store_editor` for a store read from a remaining account.
+pub fn check_store_account_editor<'info>(
+    store: &HotaStore,
+    parcel: &Option<Mutable<LoadedParcel<'info, '_>>>,
+    signer: Pubkey,
+) -> () {
+    if store.parcel == Pubkey::default() {
+        if !(signer == store.authority
+            || (signer != Pubkey::default() && store.delegates.contains(&signer)))
+        {
+            panic!("Authority or delegate of store must be signer");
+        }
+
+        return;
+    }
+
+    check_parcel_editor(store.parcel, parcel, signer);
+}
+
+pub fn check_parcel_editor<'info>(
+    store_parcel: Pubkey,
+    parcel: &Option<Mutable<LoadedParcel<'info, '_>>>,
+    signer: Pubkey,
+) -> () {
+    let parcel = parcel.as_ref().expect("Parcel of store must be given");
+
+    if !(parcel.borrow().__account__.key() == store_parcel) {
+        panic!("Parcel does not match store");
+    }
+
+    if !(signer == parcel.borrow().owner
+        || (signer != Pubkey::default() && parcel.borrow().delegates.borrow().contains(&signer)))
+    {
+        panic!("Owner or delegate of parcel must be signer");
+    }
+}
+
+/// Reads the store behind a remaining account.
+pub fn read_store_account<'info>(info: &AccountInfo<'info>) -> HotaStore {
+    if !(info.owner == &id()) {
+        panic!("Store account is not owned by the program");
+    }
+
+    let data = info.try_borrow_data().unwrap();
+    let store = HotaStore::try_deserialize(&mut &data[..]).expect("Account is not a store");
+
+    if !(store.version == STORE_VERSION && data.len() == HotaStore::space(store.fan_out)) {
+        panic!("Store must be migrated with migrate_store");
+    }
+
+    return store;
+}
+
+/// Closes the program account `info`, refunding its rent to `receiver`.
+pub fn close_program_account<'info>(
+    info: &AccountInfo<'info>,
+    receiver: &AccountInfo<'info>,
+) -> () {
+    let lamports = info.lamports();
+
+    **receiver.lamports.borrow_mut() += lamports;
+    **info.lamports.borrow_mut() = 0;
+
+    info.assign(&solana_program::system_program::ID);
+    info.realloc(0, false).unwrap();
+}
+
+/// Writes `store` back to a remaining account.
+pub fn write_store_account<'info>(info: &AccountInfo<'info>, store: &HotaStore) -> () {
+    if !(info.is_writable) {
+        panic!("Store account must be writable");
+    }
+
+    let mut data = info.try_borrow_mut_data().unwrap();
+
+    store.try_serialize(&mut &mut data[..]).unwrap();
+}
+
+/// Offset of the `lod_materials` entry of slot `index` in a store account.
+pub fn store_material_offset(index: u8) -> usize {
+    return HotaStore::SLOTS_OFFSET + 4 + index as usize;
+}
+
+/// Offset of the `pubkeys` entry of slot `index` in a store account with
+/// `fan_out` slots.
+pub fn store_child_offset(fan_out: u16, index: u8) -> usize {
+    return HotaStore::SLOTS_OFFSET + 4 + fan_out as usize + 4 + 32 * index as usize;
+}
+
+fn check_store_index(fan_out: u16, index: u8) -> () {
+    if !((index as u16) < fan_out) {
+        panic!("Index must be < fan out of store");
+    }
+}
+
+/// Writes the empty slot entries of the store account `info`.
+pub fn init_store_slots<'info>(info: &AccountInfo<'info>, fan_out: u16) -> () {
+    if !(info.is_writable) {
+        panic!("Store account must be writable");
+    }
+
+    let mut data = info.try_borrow_mut_data().unwrap();
+    let materials = HotaStore::SLOTS_OFFSET;
+    let pubkeys = materials + 4 + fan_out as usize;
+    let end = pubkeys + 4 + 32 * fan_out as usize;
+
+    data[materials..materials + 4].copy_from_slice(&(fan_out as u32).to_le_bytes());
+    data[materials + 4..pubkeys].fill(0);
+    data[pubkeys..pubkeys + 4].copy_from_slice(&(fan_out as u32).to_le_bytes());
+    data[pubkeys + 4..end].fill(0);
+}
+
+/// Child in slot `index` of the store account `info`, read in place.
+pub fn read_store_child<'info>(info: &AccountInfo<'info>, fan_out: u16, index: u8) -> Pubkey {
+    check_store_index(fan_out, index);
+
+    let offset = store_child_offset(fan_out, index);
+    let data = info.try_borrow_data().unwrap();
+
+    return Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+}
+
+/// Sets the child in slot `index` of the store account `info` in place.
+pub fn write_store_child<'info>(
+    info: &AccountInfo<'info>,
+    fan_out: u16,
+    index: u8,
+    child: Pubkey,
+) -> () {
+    check_store_index(fan_out, index);
+
+    if !(info.is_writable) {
+        panic!("Store account must be writable");
+    }
+
+    let offset = store_child_offset(fan_out, index);
+
+    info.try_borrow_mut_data().unwrap()[offset..offset + 32].copy_from_slice(child.as_ref());
+}
+
+/// Sets the dominant material of slot `index` of the store account `info`
+/// in place.
+pub fn write_store_material<'info>(
+    info: &AccountInfo<'info>,
+    fan_out: u16,
+    index: u8,
+    material: u8,
+) -> () {
+    check_store_index(fan_out, index);
+
+    if !(info.is_writable) {
+        panic!("Store account must be writable");
+    }
+
+    info.try_borrow_mut_data().unwrap()[store_material_offset(index)] = material;
+}
+
+/// Dominant materials of every slot of the store account `info`.
+pub fn read_store_materials<'info>(info: &AccountInfo<'info>, fan_out: u16) -> Vec<u8> {
+    let offset = store_material_offset(0);
+
+    return info.try_borrow_data().unwrap()[offset..offset + fan_out as usize].to_vec();
+}
+
+/// Child in slot `index` of `store`.
+pub fn store_child<'info>(store: &Mutable<LoadedHotaStore<'info, '_>>, index: u8) -> Pubkey {
+    let info = store.borrow().__account__.to_account_info();
+
+    return read_store_child(&info, store.borrow().fan_out, index);
+}
+
+/// Puts `child` into the entry of slot `index` of `store`, without touching
+/// its occupancy.
+pub fn set_store_child<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    index: u8,
+    child: Pubkey,
+) -> () {
+    let info = store.borrow().__account__.to_account_info();
+
+    write_store_child(&info, store.borrow().fan_out, index, child);
+}
+
+/// Reads the block behind a remaining account.
+pub fn read_block_account<'info>(info: &AccountInfo<'info>) -> Block {
+    if !(info.owner == &id()) {
+        panic!("Block account is not owned by the program");
+    }
+
+    let data = info.try_borrow_data().unwrap();
+    let block = Block::try_deserialize(&mut &data[..]).expect("Account is not a block");
+
+    if !(block.version == BLOCK_VERSION && data.len() == Block::SPACE) {
+        panic!("Block must be migrated with migrate_block");
+    }
+
+    return block;
+}
+
+/// Creates the program account `info` at the PDA of `seeds`. Like Anchor's
+/// `init`, lamports already sent to the address are kept and topped up, so
+/// funding an address in advance can not block its creation.
+pub fn create_pda_account<'info>(
+    payer: &SeahorseSigner<'info, '_>,
+    info: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> () {
+    let system_program = payer.programs.get("system_program").clone();
+    let rent = Rent::get().unwrap().minimum_balance(space);
+
+    if info.lamports() == 0 {
+        solana_program::program::invoke_signed(
+            &solana_program::system_instruction::create_account(
+                &payer.key(),
+                &info.key(),
+                rent,
+                space as u64,
+                &id(),
+            ),
+            &[payer.to_account_info(), info.clone(), system_program],
+            &[seeds],
+        )
+        .unwrap();
+
+        return;
+    }
+
+    if info.lamports() < rent {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(
+                &payer.key(),
+                &info.key(),
+                rent - info.lamports(),
+            ),
+            &[
+                payer.to_account_info(),
+                info.clone(),
+                system_program.clone(),
+            ],
+        )
+        .unwrap();
+    }
+
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::allocate(&info.key(), space as u64),
+        &[info.clone(), system_program.clone()],
+        &[seeds],
+    )
+    .unwrap();
+
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::assign(&info.key(), &id()),
+        &[info.clone(), system_program],
+        &[seeds],
+    )
+    .unwrap();
+}
+
+/// Resizes a program account to `space`, topping its rent up from `payer`.
+pub fn resize_program_account<'info>(
+    payer: &SeahorseSigner<'info, '_>,
+    info: &AccountInfo<'info>,
+    space: usize,
+) -> () {
+    let rent = Rent::get().unwrap().minimum_balance(space);
+
+    if info.lamports() < rent {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(
+                &payer.key(),
+                &info.key(),
+                rent - info.lamports(),
+            ),
+            &[
+                payer.to_account_info(),
+                info.clone(),
+                payer.programs.get("system_program").clone(),
+            ],
+        )
+        .unwrap();
+    }
+
+    info.realloc(space, false).unwrap();
+}
+
+/// Writes `block` back to a remaining account.
+pub fn write_block_account<'info>(info: &AccountInfo<'info>, block: &Block) -> () {
+    if !(info.is_writable) {
+        panic!("Block account must be writable");
+    }
+
+    let mut data = info.try_borrow_mut_data().unwrap();
+
+    block.try_serialize(&mut &mut data[..]).unwrap();
+}
+
+/// Whether the store `store` read from `info` holds `child` in any slot.
+pub fn store_account_holds<'info>(
+    info: &AccountInfo<'info>,
+    store: &HotaStore,
+    child: Pubkey,
+) -> bool {
+    return (0..store.fan_out).any(|i| {
+        (store.occupancy[(i / 8) as usize] >> (i % 8)) & 1 == 1
+            && read_store_child(info, store.fan_out, i as u8) == child
+    });
+}
+
+/// Panics unless `path` are the stores from `root` down to the parent of
+/// `store`, each holding the next one, or `path` is empty and `store` is
+/// `root`. Stores created in a lazy parent count as held before they are
+/// synced.
+pub fn check_store_path<'info>(
+    root: Pubkey,
+    path: &[AccountInfo<'info>],
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+) -> () {
+    let store_key = store.borrow().__account__.key();
+
+    if path.len() == 0 {
+        if !(store_key == root) {
+            panic!("Store path must start at the world root");
+        }
+
+        return;
+    }
+
+    if !(path[0].key() == root) {
+        panic!("Store path must start at the world root");
+    }
+
+    let stores: Vec<HotaStore> = path.iter().map(|info| read_store_account(info)).collect();
+
+    for i in 0..path.len() {
+        let (child, child_parent, child_index) = if i + 1 < path.len() {
+            (
+                path[i + 1].key(),
+                stores[i + 1].parent,
+                stores[i + 1].parent_index,
+            )
+        } else {
+            (
+                store_key,
+                store.borrow().parent,
+                store.borrow().parent_index,
+            )
+        };
+
+        let held = store_account_holds(&path[i], &stores[i], child)
+            || (stores[i].lazy
+                && child_parent == path[i].key()
+                && child == child_store_address(path[i].key(), child_index));
+
+        if !(held) {
+            panic!("Store path is broken");
+        }
+    }
+}
+
+/// Records `world` on `store` and on the stores of `path` above it that have
+/// none yet, once `path` is checked to lead from `world` down to `store`.
+pub fn set_store_path_world<'info>(
+    world: Pubkey,
+    path: &[AccountInfo<'info>],
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+) -> () {
+    for info in path.iter() {
+        let mut node = read_store_account(info);
+
+        if node.world == world {
+            continue;
+        }
+
+        if !(node.world == Pubkey::default()) {
+            panic!("Store path crosses another world");
+        }
+
+        node.world = world;
+
+        write_store_account(info, &node);
+    }
+
+    if !(store.borrow().world == world || store.borrow().world == Pubkey::default()) {
+        panic!("Store belongs to another world");
+    }
+
+    assign!(store.borrow_mut().world, world);
+}
+
+/// Panics unless `signer` is the authority or a delegate of `store`.
+pub fn check_store_authority<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    signer: Pubkey,
+) -> () {
+    if signer == store.borrow().authority {
+        return;
+    }
+
+    if !(signer != Pubkey::default() && store.borrow().delegates.borrow().contains(&signer)) {
+        panic!("Authority or delegate of store must be signer");
+    }
+}
+
+pub fn store_slot_occupied<'info>(store: &Mutable<LoadedHotaStore<'info, '_>>, index: u8) -> bool {
+    let byte = store.borrow().occupancy.borrow()[(index / 8) as usize];
+
+    return (byte >> (index % 8)) & 1 == 1;
+}
+
+/// Clears slot `index` of `store`, which must hold `child`.
+pub fn release_store_slot<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    index: u8,
+    child: Pubkey,
+) -> () {
+    if !((index as u16) < store.borrow().fan_out
+        && store_slot_occupied(store, index)
+        && store_child(store, index) == child)
+    {
+        panic!("Store slot does not hold the given account");
+    }
+
+    set_store_child(store, index, Pubkey::default());
+
+    index_assign!(
+        store.borrow_mut().occupancy.borrow_mut(),
+        (index / 8) as usize,
+        store.borrow().occupancy.borrow()[(index / 8) as usize] & !(1 << (index % 8))
+    );
+
+    assign!(
+        store.borrow_mut().child_count,
+        store.borrow().child_count - 1
+    );
+
+    set_store_slot_lod(store, index, 0, false);
+}
+
+/// Records the level of detail summary of slot `index` of `store`.
+pub fn set_store_slot_lod<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    index: u8,
+    material: u8,
+    solid: bool,
+) -> () {
+    let info = store.borrow().__account__.to_account_info();
+
+    write_store_material(&info, store.borrow().fan_out, index, material);
+
+    let byte = store.borrow().lod_solid.borrow()[(index / 8) as usize];
+
+    index_assign!(
+        store.borrow_mut().lod_solid.borrow_mut(),
+        (index / 8) as usize,
+        if solid {
+            byte | (1 << (index % 8))
+        } else {
+            byte & !(1 << (index % 8))
+        }
+    );
+}
+
+pub fn check_store_slot_free<'info>(store: &Mutable<LoadedHotaStore<'info, '_>>, index: u8) -> () {
+    if !((index as u16) < store.borrow().fan_out) {
+        panic!("Index must be < fan out of store");
+    }
+
+    if !(!store_slot_occupied(store, index)) {
+        panic!("Store with given index not available");
+    }
+}
+
+/// Lowest free slot of `store`.
+pub fn first_free_store_slot<'info>(store: &Mutable<LoadedHotaStore<'info, '_>>) -> u8 {
+    for index in 0..store.borrow().fan_out {
+        if !store_slot_occupied(store, index as u8) {
+            return index as u8;
+        }
+    }
+
+    panic!("Store is full");
+}
+
+/// Puts `child` into the free slot `index` of `store`.
+pub fn occupy_store_slot<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    index: u8,
+    child: Pubkey,
+) -> () {
+    if !(!store.borrow().lazy) {
+        panic!("Store is lazy, claim its slots instead");
+    }
+
+    check_store_slot_free(store, index);
+
+    set_store_child(store, index, child);
+
+    index_assign!(
+        store.borrow_mut().occupancy.borrow_mut(),
+        (index / 8) as usize,
+        store.borrow().occupancy.borrow()[(index / 8) as usize] | (1 << (index % 8))
+    );
+
+    assign!(
+        store.borrow_mut().child_count,
+        store.borrow().child_count + 1
+    );
+}
+
+/// Block coordinate, signed so worlds extend around the origin. The layout
+/// matches the former `u64` components, so coordinates below `2^63` written
+/// before read back unchanged.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug, Default, PartialEq)]
+pub struct XYZ {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+/// Creates `block` at `xyz` of `world`, claiming the cell through
+/// `block_cell`. A bought parcel covering `xyz` only lets its owner or
+/// delegates build there, see `check_coordinate_parcel` for `path`.
+pub fn init_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner: SeahorseSigner<'info, '_>,
+    mut world: Pubkey,
+    mut world_settings: Mutable<LoadedWorld<'info, '_>>,
+    mut block: Empty<Mutable<LoadedBlock<'info, '_>>>,
+    mut block_cell: Empty<Mutable<LoadedBlockCell<'info, '_>>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+    mut xyz: XYZ,
+    mut price: u64,
+    mut data: [u8; 512],
+    mut seed_random: u128,
+) -> () {
+    check_coordinate_parcel(
+        world,
+        world_settings.borrow().parcel_depth,
+        &parcel,
+        path,
+        &xyz,
+        owner.key(),
+    );
+
+    let mut block = block.account.clone();
+    let mut block_cell = block_cell.account.clone();
+    let slot = Clock::get().unwrap().slot;
+
+    assign!(block_cell.borrow_mut().world, world);
+
+    assign!(
+        block_cell.borrow_mut().xyz,
+        Mutable::<XYZ>::new(xyz.clone())
+    );
+
+    assign!(
+        block_cell.borrow_mut().block,
+        block.borrow().__account__.key()
+    );
+
+    assign!(block.borrow_mut().owner, owner.key());
+
+    assign!(block.borrow_mut().world, world);
+
+    assign!(
+        block.borrow_mut().content_hash,
+        Mutable::<[u8; 32]>::new(block_content_hash(&xyz, &data))
+    );
+
+    assign!(block.borrow_mut().xyz, Mutable::<XYZ>::new(xyz));
+
+    assign!(block.borrow_mut().price, price);
+
+    assign!(block.borrow_mut().data, Mutable::<[u8; 512]>::new(data));
+
+    assign!(block.borrow_mut().created_at, slot);
+
+    assign!(block.borrow_mut().updated_at, slot);
+}
+
+pub fn init_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Empty<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut depth: u8,
+    mut fan_out: u16,
+    mut seed_random: u128,
+) -> () {
+    if !(depth > 0) {
+        panic!("depth of store much > 0");
+    }
+
+    if !(fan_out > 0 && fan_out <= STORE_MAX_FAN_OUT) {
+        panic!("Fan out of store must be between 1 and 256");
+    }
+
+    let mut hota_store = hota_store.account.clone();
+
+    assign!(hota_store.borrow_mut().depth, depth);
+
+    assign!(hota_store.borrow_mut().fan_out, fan_out);
+
+    assign!(hota_store.borrow_mut().mapping, COORDINATE_MAPPING_CENTERED);
+
+    assign!(hota_store.borrow_mut().authority, authority.key());
+
+    assign!(hota_store.borrow_mut().creator, payer.key());
+
+    init_store_slots(&hota_store.borrow().__account__.to_account_info(), fan_out);
+}
+
+pub fn set_block_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+    mut index: u8,
+) -> () {
+    check_store_editor(&hota_store, &parcel, authority.key());
+
+    if !((block_permissions(&block, owner_block.key()) & BLOCK_PERMISSION_PLACE) != 0) {
+        panic!("Owner of block or delegate with place permission must be signer");
+    }
+
+    place_block(&hota_store, &block, path, index);
+}
+
+/// Puts `block` into slot `index` of the depth 1 `store` and records the
+/// slot on the block. `path` leads from the world of the block to `store`.
+pub fn place_block<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    block: &Mutable<LoadedBlock<'info, '_>>,
+    path: &[AccountInfo<'info>],
+    index: u8,
+) -> () {
+    check_block_slot(store, block, path, index);
+
+    set_store_path_world(block.borrow().world, path, store);
+
+    occupy_store_slot(store, index, block.borrow().__account__.key());
+
+    assign!(block.borrow_mut().store, store.borrow().__account__.key());
+
+    assign!(block.borrow_mut().store_index, index);
+}
+
+/// Panics unless the unplaced `block` may go into slot `index` of `store`,
+/// which `path` must tie to the world of the block.
+pub fn check_block_slot<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    block: &Mutable<LoadedBlock<'info, '_>>,
+    path: &[AccountInfo<'info>],
+    index: u8,
+) -> () {
+    if !(store.borrow().depth == 1) {
+        panic!("Store much have depth = 1");
+    }
+
+    if !(block.borrow().store == Pubkey::default()) {
+        panic!("Block is already placed in a store, use move_block");
+    }
+
+    check_store_path(block.borrow().world, path, store);
+
+    if store.borrow().kind != STORE_KIND_FLAT {
+        let slot = spatial_store_slot(
+            store.borrow().kind,
+            &store.borrow().origin.borrow(),
+            &store.borrow().edge.borrow(),
+            &block.borrow().xyz.borrow(),
+        );
+
+        if !(slot == index) {
+            panic!("Block coordinate does not match the slot");
+        }
+    }
+}
+
+/// Takes `block` out of the store slot it is placed in.
+pub fn unplace_block<'info>(
+    store: &Option<Mutable<LoadedHotaStore<'info, '_>>>,
+    block: &Mutable<LoadedBlock<'info, '_>>,
+) -> () {
+    if block.borrow().store == Pubkey::default() {
+        return;
+    }
+
+    let store = store
+        .as_ref()
+        .expect("Store holding the block must be given");
+
+    if !(store.borrow().__account__.key() == block.borrow().store) {
+        panic!("Store does not hold the block");
+    }
+
+    if !(!store.borrow().lazy) {
+        panic!("Store is lazy, use vacate_store_slot");
+    }
+
+    release_store_slot(
+        store,
+        block.borrow().store_index,
+        block.borrow().__account__.key(),
+    );
+
+    assign!(block.borrow_mut().store, Pubkey::default());
+
+    assign!(block.borrow_mut().store_index, 0);
+}
+
+/// Links `lo_store` into slot `index` of `hi_store`. `authority` must be
+/// allowed to edit `hi_store` and `child_authority` to edit `lo_store`;
+/// both may be the same signer. See `check_store_world` for `path`.
+pub fn set_ele_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut child_authority: SeahorseSigner<'info, '_>,
+    mut hi_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut lo_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+    mut index: u8,
+) -> () {
+    check_store_editor(&hi_store, &parcel, authority.key());
+
+    if !(lo_store.borrow().depth > 0) {
+        panic!("low node much have depth > 0");
+    }
+
+    if !(hi_store.borrow().depth == (lo_store.borrow().depth + 1)) {
+        panic!("high node much have depth = low node depth + 1");
+    }
+
+    if !(lo_store.borrow().parcel == hi_store.borrow().parcel
+        || (lo_store.borrow().parcel == Pubkey::default()
+            && lo_store.borrow().child_count == 0
+            && !lo_store.borrow().lazy))
+    {
+        panic!("low node belongs to another parcel");
+    }
+
+    check_store_editor(&lo_store, &parcel, child_authority.key());
+
+    check_store_world(&hi_store, &lo_store, path);
+
+    assign!(lo_store.borrow_mut().parcel, hi_store.borrow().parcel);
+
+    link_store(&hi_store, &lo_store, index);
+}
+
+/// Puts `child` into slot `index` of `parent` and records the slot on the
+/// child, which must not be linked yet.
+pub fn link_store<'info>(
+    parent: &Mutable<LoadedHotaStore<'info, '_>>,
+    child: &Mutable<LoadedHotaStore<'info, '_>>,
+    index: u8,
+) -> () {
+    if !(child.borrow().parent == Pubkey::default()) {
+        panic!("low node is already linked, use relink_store");
+    }
+
+    let derived = child.borrow().__account__.key()
+        == child_store_address(parent.borrow().__account__.key(), index);
+
+    if !(derived || !parent.borrow().derived) {
+        panic!("Children of a derived store must live at their derived address");
+    }
+
+    if derived {
+        assign!(parent.borrow_mut().derived, true);
+    }
+
+    if parent.borrow().kind != STORE_KIND_FLAT {
+        let (origin, edge) = child_store_box(
+            parent.borrow().kind,
+            &parent.borrow().origin.borrow(),
+            &parent.borrow().edge.borrow(),
+            index,
+        );
+
+        if !(child.borrow().kind == parent.borrow().kind
+            && *child.borrow().origin.borrow() == origin
+            && *child.borrow().edge.borrow() == edge)
+        {
+            panic!("low node does not cover the octant of the slot");
+        }
+    }
+
+    occupy_store_slot(parent, index, child.borrow().__account__.key());
+
+    assign!(child.borrow_mut().parent, parent.borrow().__account__.key());
+
+    assign!(child.borrow_mut().parent_index, index);
+}
+
+/// Panics unless the subtree of `child` may go below `parent` without its
+/// blocks leaving their world. A subtree without blocks goes anywhere; one
+/// holding blocks needs `parent` in its world, which `path` from the world
+/// root down to `parent` proves while `parent` has not recorded it yet.
+/// World roots are never linked.
+pub fn check_store_world<'info>(
+    parent: &Mutable<LoadedHotaStore<'info, '_>>,
+    child: &Mutable<LoadedHotaStore<'info, '_>>,
+    path: &[AccountInfo<'info>],
+) -> () {
+    let world = child.borrow().world;
+
+    if !(world != child.borrow().__account__.key()) {
+        panic!("World roots can not be linked");
+    }
+
+    if world == Pubkey::default() || parent.borrow().world == world {
+        return;
+    }
+
+    check_store_path(world, path, parent);
+
+    set_store_path_world(world, path, parent);
+}
+
+/// Takes `child` out of its slot in `parent`.
+pub fn unlink_store<'info>(
+    parent: &Mutable<LoadedHotaStore<'info, '_>>,
+    child: &Mutable<LoadedHotaStore<'info, '_>>,
+) -> () {
+    if !(child.borrow().parent == parent.borrow().__account__.key()) {
+        panic!("Store is not a child of the given parent");
+    }
+
+    if !(child.borrow().parcel == parent.borrow().parcel) {
+        panic!("Parcel stores can not be detached");
+    }
+
+    release_store_slot(
+        parent,
+        child.borrow().parent_index,
+        child.borrow().__account__.key(),
+    );
+
+    assign!(child.borrow_mut().parent, Pubkey::default());
+
+    assign!(child.borrow_mut().parent_index, 0);
+}
+
+pub fn trade_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut old_onwer: SeahorseSigner<'info, '_>,
+    mut new_owner: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut max_price: u64,
+    mut min_price: u64,
+) -> () {
+    if !(block.borrow().price > 0) {
+        panic!("Block not for sale");
+    }
+
+    if !(old_onwer.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    check_price_limits(block.borrow().price, max_price, min_price);
+
+    if !(!block_lease_active(&block)) {
+        panic!("Block can not be traded while leased");
+    }
+
+    solana_program::program::invoke(
+        &solana_program::system_instruction::transfer(
+            &new_owner.key(),
+            &old_onwer.clone().key(),
+            block.borrow().price.clone(),
+        ),
+        &[
+            new_owner.to_account_info(),
+            old_onwer.clone().to_account_info(),
+            new_owner.programs.get("system_program").clone(),
+        ],
+    )
+    .unwrap();
+
+    set_block_owner(&block, new_owner.key());
+}
+
+/// Aborts a purchase when the price moved above the buyer's `max_price` or
+/// below the seller's `min_price` since they signed.
+pub fn check_price_limits(price: u64, max_price: u64, min_price: u64) -> () {
+    if !(price <= max_price) {
+        panic!("Price is above the buyer max price");
+    }
+
+    if !(price >= min_price) {
+        panic!("Price is below the seller min price");
+    }
+}
+
+pub fn update_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut block_history: Option<Mutable<LoadedBlockHistory<'info, '_>>>,
+    mut block_meta: Option<Mutable<LoadedBlockMeta<'info, '_>>>,
+    mut xyz: XYZ,
+    mut price: u64,
+    mut data: [u8; 512],
+    mut expected_revision: u64,
+) -> () {
+    check_block_revision(&block, expected_revision);
+
+    if !(*block.borrow().xyz.borrow() == xyz) {
+        panic!("Use move_block to change block coordinates");
+    }
+
+    let public_editable = block_meta.map_or(false, |block_meta| {
+        (block_meta.borrow().flags & BLOCK_META_FLAG_PUBLIC_EDITABLE) != 0
+    });
+
+    check_block_edit(
+        &block,
+        owner_block.key(),
+        &xyz,
+        price,
+        &data,
+        public_editable,
+    );
+
+    assign!(block.borrow_mut().price, price);
+
+    // Price changes are not content, they only bump the revision and stay
+    // out of the history chain
+    if !(*block.borrow().data.borrow() == data) {
+        commit_block_revision(&block, block_history, xyz, data);
+    } else {
+        assign!(block.borrow_mut().revision, block.borrow().revision + 1);
+    }
+}
+
+/// Panics when `block` was updated after the client read `expected_revision`,
+/// so concurrent writers re-read and merge instead of overwriting each other.
+pub fn check_block_revision<'info>(
+    block: &Mutable<LoadedBlock<'info, '_>>,
+    expected_revision: u64,
+) -> () {
+    if !(block.borrow().revision == expected_revision) {
+        panic!("Block revision conflict, block changed since it was read");
+    }
+}
+
+/// Panics unless `signer` may set `block` to the given content and price.
+/// `public_editable` opens voxel edits to anyone while the block is not
+/// leased.
+pub fn check_block_edit<'info>(
+    block: &Mutable<LoadedBlock<'info, '_>>,
+    signer: Pubkey,
+    xyz: &XYZ,
+    price: u64,
+    data: &[u8; 512],
+    public_editable: bool,
+) -> () {
+    if !(!block.borrow().frozen
+        || (*block.borrow().xyz.borrow() == *xyz && *block.borrow().data.borrow() == *data))
+    {
+        panic!("Block is frozen");
+    }
+
+    let permissions = block_permissions(block, signer);
+    let leased = block_lease_active(block);
+    let is_renter = leased && signer == block.borrow().renter;
+
+    if !(permissions != 0 || is_renter || (!leased && public_editable)) {
+        panic!("Owner, delegate or renter of block must be signer");
+    }
+
+    if !(*block.borrow().xyz.borrow() == *xyz
+        || (!leased && (permissions & BLOCK_PERMISSION_EDIT_VOXELS) != 0))
+    {
+        panic!("Signer is not allowed to move block");
+    }
+
+    if !(*block.borrow().data.borrow() == *data
+        || (leased && is_renter)
+        || (!leased && ((permissions & BLOCK_PERMISSION_EDIT_VOXELS) != 0 || public_editable)))
+    {
+        panic!("Signer is not allowed to edit block voxels");
+    }
+
+    if !(block.borrow().price == price || (permissions & BLOCK_PERMISSION_EDIT_PRICE) != 0) {
+        panic!("Signer is not allowed to edit block price");
+    }
+}
+
+/// Starts a new revision of `block` with the given content, chaining the
+/// replaced content hash into `history_hash` and, when a history account is
+/// given, keeping the replaced content in it.
+pub fn commit_block_revision<'info>(
+    block: &Mutable<LoadedBlock<'info, '_>>,
+    block_history: Option<Mutable<LoadedBlockHistory<'info, '_>>>,
+    xyz: XYZ,
+    data: [u8; 512],
+) -> () {
+    if let Some(block_history) = block_history {
+        if !(block_history.borrow().block == block.borrow().__account__.key()) {
+            panic!("Block history does not belong to block");
+        }
+
+        let head = block_history.borrow().head as usize;
+        let len = block_history.borrow().len as usize;
+
+        index_assign!(
+            block_history.borrow_mut().entries.borrow_mut(),
+            head,
+            BlockRevision {
+                revision: block.borrow().revision,
+                updated_at: block.borrow().updated_at,
+                xyz: block.borrow().xyz.borrow().clone(),
+                data: block.borrow().data.borrow().clone(),
+            }
+        );
+
+        assign!(
+            block_history.borrow_mut().head,
+            ((head + 1) % BLOCK_HISTORY_LEN) as u8
+        );
+
+        assign!(
+            block_history.borrow_mut().len,
+            (len + 1).min(BLOCK_HISTORY_LEN) as u8
+        );
+    }
+
+    let history_hash = solana_program::hash::hashv(&[
+        &block.borrow().history_hash.borrow()[..],
+        &block.borrow().content_hash.borrow()[..],
+    ])
+    .to_bytes();
+
+    assign!(
+        block.borrow_mut().history_hash,
+        Mutable::<[u8; 32]>::new(history_hash)
+    );
+
+    assign!(
+        block.borrow_mut().content_hash,
+        Mutable::<[u8; 32]>::new(block_content_hash(&xyz, &data))
+    );
+
+    assign!(block.borrow_mut().xyz, Mutable::<XYZ>::new(xyz));
+
+    assign!(block.borrow_mut().data, Mutable::<[u8; 512]>::new(data));
+
+    assign!(block.borrow_mut().revision, block.borrow().revision + 1);
+
+    assign!(block.borrow_mut().updated_at, Clock::get().unwrap().slot);
+}
+
+pub fn block_content_hash(xyz: &XYZ, data: &[u8; 512]) -> [u8; 32] {
+    return solana_program::hash::hashv(&[
+        &xyz.x.to_le_bytes(),
+        &xyz.y.to_le_bytes(),
+        &xyz.z.to_le_bytes(),
+        &data[..],
+    ])
+    .to_bytes();
+}
+
+pub fn add_block_delegate_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut delegate: Pubkey,
+    mut permissions: u8,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    if !(permissions != 0 && (permissions & !BLOCK_PERMISSIONS_ALL) == 0) {
+        panic!("Invalid block delegate permissions");
+    }
+
+    if !(delegate != block.borrow().owner) {
+        panic!("Owner of block can not be a delegate");
+    }
+
+    let mut slot = BLOCK_MAX_DELEGATES;
+
+    for i in 0..BLOCK_MAX_DELEGATES {
+        let in_use = block.borrow().delegate_permissions.borrow()[i] != 0;
+
+        if in_use && block.borrow().delegates.borrow()[i] == delegate {
+            slot = i;
+
+            break;
+        }
+
+        if !in_use && slot == BLOCK_MAX_DELEGATES {
+            slot = i;
+        }
+    }
+
+    if !(slot < BLOCK_MAX_DELEGATES) {
+        panic!("Block already has the maximum number of delegates");
+    }
+
+    index_assign!(block.borrow_mut().delegates.borrow_mut(), slot, delegate);
+
+    index_assign!(
+        block.borrow_mut().delegate_permissions.borrow_mut(),
+        slot,
+        permissions
+    );
+}
+
+pub fn revoke_block_delegate_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut delegate: Pubkey,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    let mut found = false;
+
+    for i in 0..BLOCK_MAX_DELEGATES {
+        if block.borrow().delegate_permissions.borrow()[i] != 0
+            && block.borrow().delegates.borrow()[i] == delegate
+        {
+            index_assign!(
+                block.borrow_mut().delegates.borrow_mut(),
+                i,
+                Pubkey::default()
+            );
+
+            index_assign!(block.borrow_mut().delegate_permissions.borrow_mut(), i, 0);
+
+            found = true;
+        }
+    }
+
+    if !found {
+        panic!("Delegate not found on block");
+    }
+}
+
+/// Permissions `signer` holds on `block`: every permission for the owner,
+/// the granted set for a delegate and 0 for anyone else.
+pub fn block_permissions<'info>(block: &Mutable<LoadedBlock<'info, '_>>, signer: Pubkey) -> u8 {
+    if signer == block.borrow().owner {
+        return BLOCK_PERMISSIONS_ALL;
+    }
+
+    for i in 0..BLOCK_MAX_DELEGATES {
+        let permissions = block.borrow().delegate_permissions.borrow()[i];
+
+        if permissions != 0 && block.borrow().delegates.borrow()[i] == signer {
+            return permissions;
+        }
+    }
+
+    return 0;
+}
+
+/// Hands `block` to `new_owner`; delegates, lease offer and pending
+/// transfer of the previous owner do not carry over.
+pub fn set_block_owner<'info>(block: &Mutable<LoadedBlock<'info, '_>>, new_owner: Pubkey) -> () {
+    assign!(block.borrow_mut().owner, new_owner);
+
+    assign!(block.borrow_mut().pending_owner, Pubkey::default());
+
+    assign!(
+        block.borrow_mut().delegates,
+        Mutable::<[Pubkey; 8]>::new([Pubkey::default(); 8])
+    );
+
+    assign!(
+        block.borrow_mut().delegate_permissions,
+        Mutable::<[u8; 8]>::new([0; 8])
+    );
+
+    assign!(block.borrow_mut().lease_rate, 0);
+}
+
+/// Whether a renter currently holds the voxel edit rights of `block`.
+pub fn block_lease_active<'info>(block: &Mutable<LoadedBlock<'info, '_>>) -> bool {
+    return Clock::get().unwrap().slot < block.borrow().lease_expiry;
+}
+
+pub fn init_block_meta_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut block_meta: Empty<Mutable<LoadedBlockMeta<'info, '_>>>,
+    mut biome: u8,
+    mut light: u8,
+    mut flags: u8,
+    mut name: [u8; 32],
+    mut uri: [u8; 128],
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    let mut block_meta = block_meta.account.clone();
+
+    assign!(block_meta.borrow_mut().block, block.borrow().__account__.key());
+
+    set_block_meta_attributes(block_meta, biome, light, flags, name, uri);
+}
+
+pub fn update_block_meta_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut block_meta: Mutable<LoadedBlockMeta<'info, '_>>,
+    mut biome: u8,
+    mut light: u8,
+    mut flags: u8,
+    mut name: [u8; 32],
+    mut uri: [u8; 128],
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    if !(block_meta.borrow().block == block.borrow().__account__.key()) {
+        panic!("Block meta does not belong to block");
+    }
+
+    set_block_meta_attributes(block_meta, biome, light, flags, name, uri);
+}
+
+fn set_block_meta_attributes<'info>(
+    mut block_meta: Mutable<LoadedBlockMeta<'info, '_>>,
+    mut biome: u8,
+    mut light: u8,
+    mut flags: u8,
+    mut name: [u8; 32],
+    mut uri: [u8; 128],
+) -> () {
+    if !(light <= BLOCK_META_MAX_LIGHT) {
+        panic!("Light emission must be <= 15");
+    }
+
+    if !((flags & !BLOCK_META_FLAGS_ALL) == 0) {
+        panic!("Unknown block meta flags");
+    }
+
+    if !((block_meta.borrow().flags & BLOCK_META_FLAG_INDESTRUCTIBLE) == 0
+        || (flags & BLOCK_META_FLAG_INDESTRUCTIBLE) != 0)
+    {
+        panic!("Indestructible flag can not be cleared");
+    }
+
+    assign!(block_meta.borrow_mut().biome, biome);
+
+    assign!(block_meta.borrow_mut().light, light);
+
+    assign!(block_meta.borrow_mut().flags, flags);
+
+    assign!(block_meta.borrow_mut().name, Mutable::<[u8; 32]>::new(name));
+
+    assign!(block_meta.borrow_mut().uri, Mutable::<[u8; 128]>::new(uri));
+}
+
+pub fn offer_lease_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut rate: u64,
+    mut max_slots: u64,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    if !(rate == 0 || !block.borrow().frozen) {
+        panic!("Block is frozen");
+    }
+
+    if !(rate == 0 || max_slots > 0) {
+        panic!("Lease offer must allow at least one slot");
+    }
+
+    assign!(block.borrow_mut().lease_rate, rate);
+
+    assign!(block.borrow_mut().lease_max_slots, max_slots);
+}
+
+pub fn rent_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut renter: SeahorseSigner<'info, '_>,
+    mut owner: UncheckedAccount<'info>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut slots: u64,
+    mut max_rate: u64,
+) -> () {
+    if !(block.borrow().lease_rate > 0) {
+        panic!("Block not for rent");
+    }
+
+    if !(!block.borrow().frozen) {
+        panic!("Block is frozen");
+    }
+
+    if !(owner.key() == block.borrow().owner) {
+        panic!("Owner account does not match block owner");
+    }
+
+    if !(renter.key() != block.borrow().owner) {
+        panic!("Owner of block can not rent it");
+    }
+
+    check_price_limits(block.borrow().lease_rate, max_rate, 0);
+
+    if !(!block_lease_active(&block)) {
+        panic!("Block is already leased");
+    }
+
+    if !(slots > 0 && slots <= block.borrow().lease_max_slots) {
+        panic!("Lease length must be between 1 and the offered max slots");
+    }
+
+    let cost = block
+        .borrow()
+        .lease_rate
+        .checked_mul(slots)
+        .expect("Lease cost overflow");
+
+    solana_program::program::invoke(
+        &solana_program::system_instruction::transfer(&renter.key(), &owner.key(), cost),
+        &[
+            renter.to_account_info(),
+            owner.to_account_info(),
+            renter.programs.get("system_program").clone(),
+        ],
+    )
+    .unwrap();
+
+    assign!(block.borrow_mut().renter, renter.key());
+
+    let lease_expiry = Clock::get()
+        .unwrap()
+        .slot
+        .checked_add(slots)
+        .expect("Lease expiry overflow");
+
+    assign!(block.borrow_mut().lease_expiry, lease_expiry);
+}
+
+pub fn freeze_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    if !(!block.borrow().frozen) {
+        panic!("Block is frozen");
+    }
+
+    if !(!block_lease_active(&block)) {
+        panic!("Block can not be frozen while leased");
+    }
+
+    assign!(block.borrow_mut().frozen, true);
+
+    assign!(block.borrow_mut().lease_rate, 0);
+}
+
+pub fn init_block_history_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut block_history: Empty<Mutable<LoadedBlockHistory<'info, '_>>>,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    let mut block_history = block_history.account.clone();
+
+    assign!(
+        block_history.borrow_mut().block,
+        block.borrow().__account__.key()
+    );
+}
+
+pub fn revert_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut block_history: Mutable<LoadedBlockHistory<'info, '_>>,
+    mut revision: u64,
+    mut expected_revision: u64,
+) -> () {
+    check_block_revision(&block, expected_revision);
+
+    let mut target: Option<BlockRevision> = None;
+
+    for i in 0..(block_history.borrow().len as usize) {
+        let entry = block_history.borrow().entries.borrow()[i].clone();
+
+        if entry.revision == revision {
+            target = Some(entry);
+        }
+    }
+
+    let target = target.expect("Revision not found in block history");
+    let price = block.borrow().price;
+    // Only the content is reverted, coordinates are owned by the block cell
+    let xyz = block.borrow().xyz.borrow().clone();
+
+    check_block_edit(&block, owner_block.key(), &xyz, price, &target.data, false);
+
+    commit_block_revision(&block, Some(block_history), xyz, target.data);
+}
+
+pub fn transfer_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut new_owner: Pubkey,
+    mut require_accept: bool,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    if !(new_owner != Pubkey::default() && new_owner != block.borrow().owner) {
+        panic!("Invalid new owner of block");
+    }
+
+    if !(!block_lease_active(&block)) {
+        panic!("Block can not be transferred while leased");
+    }
+
+    if require_accept {
+        assign!(block.borrow_mut().pending_owner, new_owner);
+    } else {
+        set_block_owner(&block, new_owner);
+    }
+}
+
+pub fn accept_block_transfer_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut new_owner: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+) -> () {
+    if !(block.borrow().pending_owner != Pubkey::default()) {
+        panic!("Block has no pending transfer");
+    }
+
+    if !(new_owner.key() == block.borrow().pending_owner) {
+        panic!("Pending owner of block must be signer");
+    }
+
+    if !(!block_lease_active(&block)) {
+        panic!("Block can not be transferred while leased");
+    }
+
+    set_block_owner(&block, new_owner.key());
+}
+
+pub fn cancel_block_transfer_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    if !(block.borrow().pending_owner != Pubkey::default()) {
+        panic!("Block has no pending transfer");
+    }
+
+    assign!(block.borrow_mut().pending_owner, Pubkey::default());
+}
+
+pub fn set_block_store_auto_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+) -> () {
+    let index = first_free_store_slot(&hota_store);
+
+    set_block_store_handler(
+        payer,
+        owner_block,
+        authority,
+        hota_store,
+        block,
+        parcel,
+        path,
+        index,
+    );
+
+    solana_program::program::set_return_data(&[index]);
+}
+
+pub fn set_ele_store_auto_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut child_authority: SeahorseSigner<'info, '_>,
+    mut hi_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut lo_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+) -> () {
+    let index = first_free_store_slot(&hi_store);
+
+    set_ele_store_handler(
+        payer,
+        authority,
+        child_authority,
+        hi_store,
+        lo_store,
+        parcel,
+        path,
+        index,
+    );
+
+    solana_program::program::set_return_data(&[index]);
+}
+
+pub fn add_store_delegate_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut delegate: Pubkey,
+) -> () {
+    if !(authority.key() == hota_store.borrow().authority) {
+        panic!("Authority of store must be signer");
+    }
+
+    if !(delegate != Pubkey::default() && delegate != hota_store.borrow().authority) {
+        panic!("Invalid store delegate");
+    }
+
+    if !(!hota_store.borrow().delegates.borrow().contains(&delegate)) {
+        panic!("Delegate already added to store");
+    }
+
+    let slot = hota_store
+        .borrow()
+        .delegates
+        .borrow()
+        .iter()
+        .position(|key| *key == Pubkey::default())
+        .expect("Store already has the maximum number of delegates");
+
+    index_assign!(
+        hota_store.borrow_mut().delegates.borrow_mut(),
+        slot,
+        delegate
+    );
+}
+
+pub fn revoke_store_delegate_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut delegate: Pubkey,
+) -> () {
+    if !(authority.key() == hota_store.borrow().authority) {
+        panic!("Authority of store must be signer");
+    }
+
+    let slot = hota_store
+        .borrow()
+        .delegates
+        .borrow()
+        .iter()
+        .position(|key| *key == delegate && delegate != Pubkey::default())
+        .expect("Delegate not found on store");
+
+    index_assign!(
+        hota_store.borrow_mut().delegates.borrow_mut(),
+        slot,
+        Pubkey::default()
+    );
+}
+
+pub fn transfer_store_authority_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut new_authority: Pubkey,
+) -> () {
+    if !(authority.key() == hota_store.borrow().authority) {
+        panic!("Authority of store must be signer");
+    }
+
+    if !(new_authority != Pubkey::default()) {
+        panic!("Invalid new authority of store");
+    }
+
+    set_store_authority(&hota_store, new_authority);
+}
+
+/// Hands `store` to `new_authority`; delegates of the previous authority do
+/// not carry over.
+pub fn set_store_authority<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    new_authority: Pubkey,
+) -> () {
+    assign!(store.borrow_mut().authority, new_authority);
+
+    assign!(
+        store.borrow_mut().delegates,
+        Mutable::<[Pubkey; 4]>::new([Pubkey::default(); 4])
+    );
+}
+
+pub fn init_world_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut root_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut world: Empty<Mutable<LoadedWorld<'info, '_>>>,
+    mut treasury: Pubkey,
+    mut parcel_fee: u64,
+    mut parcel_depth: u8,
+) -> () {
+    if !(authority.key() == root_store.borrow().authority) {
+        panic!("Authority of store must be signer");
+    }
+
+    if !(root_store.borrow().parent == Pubkey::default()) {
+        panic!("World root store can not have a parent");
+    }
+
+    // Blocks below a store of another world have their cells claimed there
+    if !(root_store.borrow().world == Pubkey::default()) {
+        panic!("Store belongs to another world");
+    }
+
+    assign!(
+        root_store.borrow_mut().world,
+        root_store.borrow().__account__.key()
+    );
+
+    if !(parcel_depth > 0 && parcel_depth < root_store.borrow().depth) {
+        panic!("Parcel depth must be between 1 and the world depth");
+    }
+
+    let mut world = world.account.clone();
+
+    assign!(
+        world.borrow_mut().root,
+        root_store.borrow().__account__.key()
+    );
+
+    assign!(world.borrow_mut().authority, authority.key());
+
+    assign!(world.borrow_mut().treasury, treasury);
+
+    assign!(world.borrow_mut().parcel_fee, parcel_fee);
+
+    assign!(world.borrow_mut().parcel_depth, parcel_depth);
+}
+
+pub fn update_world_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut world: Mutable<LoadedWorld<'info, '_>>,
+    mut treasury: Pubkey,
+    mut parcel_fee: u64,
+) -> () {
+    if !(authority.key() == world.borrow().authority) {
+        panic!("Authority of world must be signer");
+    }
+
+    assign!(world.borrow_mut().treasury, treasury);
+
+    assign!(world.borrow_mut().parcel_fee, parcel_fee);
+}
+
+/// Turns the empty `parcel_store` into a parcel of `owner`, with the consent
+/// of the store authority.
+pub fn claim_parcel_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut world: Mutable<LoadedWorld<'info, '_>>,
+    mut treasury: UncheckedAccount<'info>,
+    mut parcel_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut parcel: Empty<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+) -> () {
+    if !(treasury.key() == world.borrow().treasury) {
+        panic!("Treasury account does not match world treasury");
+    }
+
+    if !(parcel_store.borrow().depth == world.borrow().parcel_depth) {
+        panic!("Store depth does not match world parcel depth");
+    }
+
+    if !(parcel_store.borrow().parcel == Pubkey::default()) {
+        panic!("Store is already part of a parcel");
+    }
+
+    check_store_authority(&parcel_store, authority.key());
+
+    // Children of lazy stores may exist before they are counted
+    if !(parcel_store.borrow().child_count == 0 && !parcel_store.borrow().lazy) {
+        panic!("Only empty stores can be claimed as parcel");
+    }
+
+    check_store_path(world.borrow().root, path, &parcel_store);
+
+    if world.borrow().parcel_fee > 0 {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(
+                &owner.key(),
+                &treasury.key(),
+                world.borrow().parcel_fee,
+            ),
+            &[
+                owner.to_account_info(),
+                treasury.to_account_info(),
+                owner.programs.get("system_program").clone(),
+            ],
+        )
+        .unwrap();
+    }
+
+    let mut parcel = parcel.account.clone();
+
+    assign!(parcel.borrow_mut().world, world.borrow().__account__.key());
+
+    assign!(
+        parcel.borrow_mut().store,
+        parcel_store.borrow().__account__.key()
+    );
+
+    assign!(parcel.borrow_mut().owner, owner.key());
+
+    assign!(
+        parcel_store.borrow_mut().parcel,
+        parcel.borrow().__account__.key()
+    );
+}
+
+pub fn set_parcel_price_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner: SeahorseSigner<'info, '_>,
+    mut parcel: Mutable<LoadedParcel<'info, '_>>,
+    mut price: u64,
+) -> () {
+    if !(owner.key() == parcel.borrow().owner) {
+        panic!("Owner of parcel must be signer");
+    }
+
+    assign!(parcel.borrow_mut().price, price);
+}
+
+pub fn trade_parcel_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut old_owner: SeahorseSigner<'info, '_>,
+    mut new_owner: SeahorseSigner<'info, '_>,
+    mut parcel: Mutable<LoadedParcel<'info, '_>>,
+    mut max_price: u64,
+    mut min_price: u64,
+) -> () {
+    if !(parcel.borrow().price > 0) {
+        panic!("Parcel not for sale");
+    }
+
+    if !(old_owner.key() == parcel.borrow().owner) {
+        panic!("Owner of parcel must be signer");
+    }
+
+    check_price_limits(parcel.borrow().price, max_price, min_price);
+
+    solana_program::program::invoke(
+        &solana_program::system_instruction::transfer(
+            &new_owner.key(),
+            &old_owner.key(),
+            parcel.borrow().price,
+        ),
+        &[
+            new_owner.to_account_info(),
+            old_owner.to_account_info(),
+            new_owner.programs.get("system_program").clone(),
+        ],
+    )
+    .unwrap();
+
+    assign!(parcel.borrow_mut().owner, new_owner.key());
+
+    assign!(
+        parcel.borrow_mut().delegates,
+        Mutable::<[Pubkey; 4]>::new([Pubkey::default(); 4])
+    );
+}
+
+pub fn add_parcel_delegate_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner: SeahorseSigner<'info, '_>,
+    mut parcel: Mutable<LoadedParcel<'info, '_>>,
+    mut delegate: Pubkey,
+) -> () {
+    if !(owner.key() == parcel.borrow().owner) {
+        panic!("Owner of parcel must be signer");
+    }
+
+    if !(delegate != Pubkey::default() && delegate != parcel.borrow().owner) {
+        panic!("Invalid parcel delegate");
+    }
+
+    if !(!parcel.borrow().delegates.borrow().contains(&delegate)) {
+        panic!("Delegate already added to parcel");
+    }
+
+    let slot = parcel
+        .borrow()
+        .delegates
+        .borrow()
+        .iter()
+        .position(|key| *key == Pubkey::default())
+        .expect("Parcel already has the maximum number of delegates");
+
+    index_assign!(parcel.borrow_mut().delegates.borrow_mut(), slot, delegate);
+}
+
+pub fn revoke_parcel_delegate_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner: SeahorseSigner<'info, '_>,
+    mut parcel: Mutable<LoadedParcel<'info, '_>>,
+    mut delegate: Pubkey,
+) -> () {
+    if !(owner.key() == parcel.borrow().owner) {
+        panic!("Owner of parcel must be signer");
+    }
+
+    let slot = parcel
+        .borrow()
+        .delegates
+        .borrow()
+        .iter()
+        .position(|key| *key == delegate && delegate != Pubkey::default())
+        .expect("Delegate not found on parcel");
+
+    index_assign!(
+        parcel.borrow_mut().delegates.borrow_mut(),
+        slot,
+        Pubkey::default()
+    );
+}
+
+pub fn init_child_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut parent: Mutable<LoadedHotaStore<'info, '_>>,
+    mut child: Empty<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut index: u8,
+) -> () {
+    check_store_editor(&parent, &parcel, authority.key());
+
+    let mut child = child.account.clone();
+
+    init_child_store_fields(payer.key(), &parent, &child, index);
+
+    link_store(&parent, &child, index);
+}
+
+/// Address of the store in slot `index` of `parent`, `["store", parent, index]`.
+pub fn child_store_address(parent: Pubkey, index: u8) -> Pubkey {
+    return Pubkey::find_program_address(&["store".as_bytes(), parent.as_ref(), &[index]], &id()).0;
+}
+
+/// Sets up `child` as the store in slot `index` of `parent`, without
+/// linking it.
+pub fn init_child_store_fields<'info>(
+    payer: Pubkey,
+    parent: &Mutable<LoadedHotaStore<'info, '_>>,
+    child: &Mutable<LoadedHotaStore<'info, '_>>,
+    index: u8,
+) -> () {
+    if !(parent.borrow().depth > 1) {
+        panic!("Parent store must have depth > 1");
+    }
+
+    assign!(child.borrow_mut().depth, parent.borrow().depth - 1);
+
+    assign!(child.borrow_mut().fan_out, parent.borrow().fan_out);
+
+    assign!(child.borrow_mut().authority, parent.borrow().authority);
+
+    assign!(child.borrow_mut().creator, payer);
+
+    assign!(child.borrow_mut().parcel, parent.borrow().parcel);
+
+    assign!(child.borrow_mut().world, parent.borrow().world);
+
+    assign!(child.borrow_mut().mapping, parent.borrow().mapping);
+
+    assign!(child.borrow_mut().lazy, parent.borrow().lazy);
+
+    assign!(child.borrow_mut().derived, true);
+
+    if parent.borrow().kind != STORE_KIND_FLAT {
+        let (origin, edge) = child_store_box(
+            parent.borrow().kind,
+            &parent.borrow().origin.borrow(),
+            &parent.borrow().edge.borrow(),
+            index,
+        );
+
+        assign!(child.borrow_mut().kind, parent.borrow().kind);
+
+        assign!(child.borrow_mut().origin, Mutable::<XYZ>::new(origin));
+
+        assign!(child.borrow_mut().edge, Mutable::<XYZ>::new(edge));
+    }
+
+    init_store_slots(
+        &child.borrow().__account__.to_account_info(),
+        parent.borrow().fan_out,
+    );
+}
+
+/// Offsets a signed coordinate into `0..2^bits` by the `mapping` of the
+/// world, one of `COORDINATE_MAPPING_*`. None when it lies outside.
+pub fn world_axis_position(value: i64, bits: u32, mapping: u8) -> Option<u64> {
+    let offset = if mapping == COORDINATE_MAPPING_CENTERED {
+        1i128 << (bits - 1)
+    } else {
+        0
+    };
+    let value = value as i128 + offset;
+
+    if value >= 0 && value < (1i128 << bits) {
+        return Some(value as u64);
+    }
+
+    return None;
+}
+
+/// Position of `xyz` in a flat world of depth `world_depth`, which covers
+/// `4^depth` blocks along x and y and `2^depth` along z, laid out by the
+/// `mapping` of its root.
+pub fn flat_world_coordinate(xyz: &XYZ, world_depth: u8, mapping: u8) -> (u64, u64, u64) {
+    if !(world_depth > 0 && world_depth <= 32) {
+        panic!("World depth must be between 1 and 32");
+    }
+
+    let depth = world_depth as u32;
+
+    match (
+        world_axis_position(xyz.x, 2 * depth, mapping),
+        world_axis_position(xyz.y, 2 * depth, mapping),
+        world_axis_position(xyz.z, depth, mapping),
+    ) {
+        (Some(x), Some(y), Some(z)) => (x, y, z),
+        _ => panic!("Coordinate is outside of the world"),
+    }
+}
+
+/// Fan out of the stores of a flat world addressed by coordinate, one slot
+/// per cell of the 4 x 4 x 2 split of `store_path_index`.
+pub const FLAT_WORLD_FAN_OUT: u16 = 32;
+
+/// Slot of the store of depth `depth` that leads to the block at the
+/// `flat_world_coordinate` `position`. Every level splits space 4 x 4 x 2,
+/// so a slot index is `x | y << 2 | z << 4` over the bits of that level,
+/// the root taking the highest bits.
+pub fn store_path_index(position: &(u64, u64, u64), depth: u8) -> u8 {
+    let shift = (depth - 1) as u32;
+    let x = position.0.checked_shr(2 * shift).unwrap_or(0) & 3;
+    let y = position.1.checked_shr(2 * shift).unwrap_or(0) & 3;
+    let z = position.2.checked_shr(shift).unwrap_or(0) & 1;
+
+    return (x | (y << 2) | (z << 4)) as u8;
+}
+
+/// Slot of `store` on the way to `xyz`, at `position` in a flat world.
+pub fn coordinate_store_slot(store: &HotaStore, position: &(u64, u64, u64), xyz: &XYZ) -> u8 {
+    if store.kind == STORE_KIND_FLAT {
+        return store_path_index(position, store.depth);
+    }
+
+    return spatial_store_slot(store.kind, &store.origin, &store.edge, xyz);
+}
+
+/// Panics unless `signer` may build at `xyz` in the world rooted at `world`:
+/// once the parcel covering `xyz` is bought, only its owner or delegates
+/// can. `path` follows the coordinate from the world root down to the store
+/// at `parcel_depth`, ending early where no store exists yet. Flat worlds
+/// not addressed by coordinate do not tie parcels to coordinates.
+pub fn check_coordinate_parcel<'info>(
+    world: Pubkey,
+    parcel_depth: u8,
+    parcel: &Option<Mutable<LoadedParcel<'info, '_>>>,
+    path: &[AccountInfo<'info>],
+    xyz: &XYZ,
+    signer: Pubkey,
+) -> () {
+    if !(path.len() > 0 && path[0].key() == world) {
+        panic!("Store path must start at the world root");
+    }
+
+    let mut store = read_store_account(&path[0]);
+
+    if store.kind == STORE_KIND_FLAT && store.fan_out != FLAT_WORLD_FAN_OUT {
+        return;
+    }
+
+    let position = if store.kind == STORE_KIND_FLAT {
+        flat_world_coordinate(xyz, store.depth, store.mapping)
+    } else {
+        (0, 0, 0)
+    };
+
+    let mut level = 0;
+    let mut used = 1;
+
+    while store.depth > parcel_depth {
+        let index = coordinate_store_slot(&store, &position, xyz);
+        let store_key = path[level].key();
+        let (child, lazy) = if (store.occupancy[(index / 8) as usize] >> (index % 8)) & 1 == 1 {
+            (read_store_child(&path[level], store.fan_out, index), false)
+        } else if store.lazy {
+            // Children of lazy stores may exist before they are synced
+            (child_store_address(store_key, index), true)
+        } else {
+            break;
+        };
+
+        let info = path
+            .get(level + 1)
+            .expect("Store path must follow the coordinate down to its parcel");
+
+        if !(info.key() == child) {
+            panic!("Store path does not follow the coordinate");
+        }
+
+        used = level + 2;
+
+        if lazy && info.data_len() == 0 {
+            break;
+        }
+
+        let next = read_store_account(info);
+
+        if lazy && next.parent != store_key {
+            break;
+        }
+
+        store = next;
+        level += 1;
+    }
+
+    if !(path.len() == used) {
+        panic!("Store path does not follow the coordinate");
+    }
+
+    if store.depth == parcel_depth && store.parcel != Pubkey::default() {
+        check_parcel_editor(store.parcel, parcel, signer);
+    }
+}
+
+pub fn create_path_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+    mut xyz: XYZ,
+) -> () {
+    if !(path.len() > 0 && read_store_account(&path[0]).depth as usize == path.len()) {
+        panic!("Store path must hold the root and one store per level below it");
+    }
+
+    let root = read_store_account(&path[0]);
+    let position = if root.kind == STORE_KIND_FLAT {
+        if !(root.fan_out == FLAT_WORLD_FAN_OUT) {
+            panic!("Flat worlds need a fan out of 32 to be addressed by coordinate");
+        }
+
+        flat_world_coordinate(&xyz, path.len() as u8, root.mapping)
+    } else {
+        (0, 0, 0)
+    };
+
+    for level in 1..path.len() {
+        let mut parent = read_store_account(&path[level - 1]);
+        let parent_key = path[level - 1].key();
+        let child_key = path[level].key();
+        let index = coordinate_store_slot(&parent, &position, &xyz);
+
+        if !((index as u16) < parent.fan_out) {
+            panic!("Index must be < fan out of store");
+        }
+
+        if (parent.occupancy[(index / 8) as usize] >> (index % 8)) & 1 == 1 {
+            if !(read_store_child(&path[level - 1], parent.fan_out, index) == child_key) {
+                panic!("Store path is broken");
+            }
+
+            continue;
+        }
+
+        let (address, bump) = Pubkey::find_program_address(
+            &["store".as_bytes(), parent_key.as_ref(), &[index]],
+            &id(),
+        );
+
+        if !(address == child_key) {
+            panic!("Store account does not match the derived child store address");
+        }
+
+        if path[level].data_len() > 0 {
+            // Children of lazy stores may exist before they are synced
+            if parent.lazy {
+                continue;
+            }
+
+            panic!("Child store exists but is detached, link it with set_ele_store");
+        }
+
+        check_store_account_editor(&parent, &parcel, authority.key());
+
+        create_pda_account(
+            &payer,
+            &path[level],
+            &["store".as_bytes(), parent_key.as_ref(), &[index], &[bump]],
+            HotaStore::space(parent.fan_out),
+        );
+
+        let (origin, edge) = if parent.kind == STORE_KIND_FLAT {
+            (XYZ::default(), XYZ::default())
+        } else {
+            child_store_box(parent.kind, &parent.origin, &parent.edge, index)
+        };
+
+        let child = HotaStore {
+            version: STORE_VERSION,
+            depth: parent.depth - 1,
+            fan_out: parent.fan_out,
+            child_count: 0,
+            occupancy: [0; STORE_BITMAP_LEN],
+            authority: parent.authority,
+            delegates: [Pubkey::default(); 4],
+            parcel: parent.parcel,
+            world: parent.world,
+            price: 0,
+            parent: parent_key,
+            parent_index: index,
+            creator: payer.key(),
+            kind: parent.kind,
+            origin,
+            edge,
+            mapping: parent.mapping,
+            lazy: parent.lazy,
+            derived: true,
+            transfer_from: Pubkey::default(),
+            transfer_blocks: false,
+            transfer_cursor: 0,
+            content_hash: [0; 32],
+            chunk_hashes: [[0; 32]; STORE_BITMAP_LEN],
+            hashed_at: 0,
+            lod_solid: [0; STORE_BITMAP_LEN],
+        };
+
+        write_store_account(&path[level], &child);
+
+        init_store_slots(&path[level], parent.fan_out);
+
+        if parent.lazy {
+            continue;
+        }
+
+        write_store_child(&path[level - 1], parent.fan_out, index, child_key);
+
+        parent.occupancy[(index / 8) as usize] |= 1 << (index % 8);
+        parent.child_count += 1;
+        parent.derived = true;
+
+        write_store_account(&path[level - 1], &parent);
+    }
+}
+
+/// Hands `store` from `seller` to `new_authority`. Stores below it that
+/// `seller` holds, and with `with_blocks` the blocks `seller` owns there,
+/// follow one level at a time through `cascade_store_transfer`, so trees of
+/// any size can change hands. Until the hand-down reaches them they stay
+/// with `seller`; sending the cascade in the same transaction closes that
+/// gap for small trees.
+pub fn transfer_store_subtree<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    seller: Pubkey,
+    new_authority: Pubkey,
+    with_blocks: bool,
+) -> () {
+    if !(new_authority != Pubkey::default()) {
+        panic!("Invalid new authority of store");
+    }
+
+    if !(store.borrow().transfer_from == Pubkey::default()) {
+        panic!("Store subtree is still being handed down");
+    }
+
+    set_store_authority(store, new_authority);
+
+    if store.borrow().child_count > 0 {
+        assign!(store.borrow_mut().transfer_from, seller);
+
+        assign!(store.borrow_mut().transfer_blocks, with_blocks);
+
+        assign!(store.borrow_mut().transfer_cursor, 0);
+    }
+}
+
+/// Hands the children of `hota_store` from its previous authority to the
+/// current one, continuing at `transfer_cursor`. `children` are the
+/// occupied slots from there on, in slot order; any prefix of them may be
+/// given and the rest handed down by a later call. Child stores take the
+/// hand-down over for the level below them. Children of other authorities
+/// or owners are left alone, leased blocks hold the hand-down until their
+/// lease ends. Anyone can cascade.
+pub fn cascade_store_transfer_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut children: &'info [AccountInfo<'info>],
+) -> () {
+    let seller = hota_store.borrow().transfer_from;
+    let with_blocks = hota_store.borrow().transfer_blocks;
+    let new_authority = hota_store.borrow().authority;
+
+    if !(seller != Pubkey::default()) {
+        panic!("Store has no pending transfer");
+    }
+
+    let fan_out = hota_store.borrow().fan_out as usize;
+    let mut index = hota_store.borrow().transfer_cursor as usize;
+    let mut next = children.iter();
+
+    while index < fan_out {
+        if (hota_store.borrow().occupancy.borrow()[index / 8] >> (index % 8)) & 1 == 1 {
+            let info = match next.next() {
+                Some(info) => info,
+                None => break,
+            };
+
+            if !(info.key() == store_child(&hota_store, index as u8)) {
+                panic!("Children must follow the store slots from the transfer cursor");
+            }
+
+            if hota_store.borrow().depth > 1 {
+                let mut account =
+                    Box::new(Account::<HotaStore>::try_from(info).expect("Account is not a store"));
+                let child = HotaStore::load(&mut account, payer.programs);
+
+                if child.borrow().authority == seller {
+                    transfer_store_subtree(&child, seller, new_authority, with_blocks);
+                }
+
+                HotaStore::store(child);
+
+                account.exit(&id()).unwrap();
+            } else if with_blocks {
+                let mut account =
+                    Box::new(Account::<Block>::try_from(info).expect("Account is not a block"));
+                let block = Block::load(&mut account, payer.programs);
+
+                if block.borrow().owner == seller {
+                    if !(!block_lease_active(&block)) {
+                        panic!("Block can not be traded while leased");
+                    }
+
+                    set_block_owner(&block, new_authority);
+                }
+
+                Block::store(block);
+
+                account.exit(&id()).unwrap();
+            }
+        }
+
+        index += 1;
+    }
+
+    if index < fan_out {
+        assign!(hota_store.borrow_mut().transfer_cursor, index as u8);
+    } else {
+        assign!(hota_store.borrow_mut().transfer_from, Pubkey::default());
+
+        assign!(hota_store.borrow_mut().transfer_blocks, false);
+
+        assign!(hota_store.borrow_mut().transfer_cursor, 0);
+    }
+}
+
+/// Hands the subtree below `store` from `seller` to `new_authority` within
+/// one instruction. `accounts` are the occupied slots of the subtree depth
+/// first, each store followed by its own slots; stores of other authorities
+/// are not descended into, and the slots of leaf stores are only needed
+/// `with_blocks`. Leased blocks fail the hand-down.
+pub fn hand_down_store_subtree<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    seller: Pubkey,
+    new_authority: Pubkey,
+    with_blocks: bool,
+    accounts: &mut std::slice::Iter<'info, AccountInfo<'info>>,
+    programs: &ProgramsMap<'info>,
+) -> () {
+    let depth = store.borrow().depth;
+
+    if depth == 1 && !with_blocks {
+        return;
+    }
+
+    for index in 0..store.borrow().fan_out as usize {
+        if (store.borrow().occupancy.borrow()[index / 8] >> (index % 8)) & 1 == 0 {
+            continue;
+        }
+
+        let info = match accounts.next() {
+            Some(info) => info,
+            None => panic!("Accounts of the store subtree must all be given"),
+        };
+
+        if !(info.key() == store_child(store, index as u8)) {
+            panic!("Accounts must follow the store subtree depth first");
+        }
+
+        if depth > 1 {
+            let mut account =
+                Box::new(Account::<HotaStore>::try_from(info).expect("Account is not a store"));
+            let child = HotaStore::load(&mut account, programs);
+
+            if child.borrow().authority == seller {
+                if !(child.borrow().transfer_from == Pubkey::default()) {
+                    panic!("Store subtree is still being handed down");
+                }
+
+                set_store_authority(&child, new_authority);
+
+                hand_down_store_subtree(
+                    &child,
+                    seller,
+                    new_authority,
+                    with_blocks,
+                    accounts,
+                    programs,
+                );
+            }
+
+            HotaStore::store(child);
+
+            account.exit(&id()).unwrap();
+        } else {
+            let mut account =
+                Box::new(Account::<Block>::try_from(info).expect("Account is not a block"));
+            let block = Block::load(&mut account, programs);
+
+            if block.borrow().owner == seller {
+                if !(!block_lease_active(&block)) {
+                    panic!("Block can not be traded while leased");
+                }
+
+                set_block_owner(&block, new_authority);
+            }
+
+            Block::store(block);
+
+            account.exit(&id()).unwrap();
+        }
+    }
+}
+
+pub fn transfer_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut new_authority: Pubkey,
+    mut with_blocks: bool,
+) -> () {
+    if !(authority.key() == hota_store.borrow().authority) {
+        panic!("Authority of store must be signer");
+    }
+
+    transfer_store_subtree(&hota_store, authority.key(), new_authority, with_blocks);
+}
+
+pub fn set_store_price_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut price: u64,
+) -> () {
+    if !(authority.key() == hota_store.borrow().authority) {
+        panic!("Authority of store must be signer");
+    }
+
+    assign!(hota_store.borrow_mut().price, price);
+}
+
+/// Sells `hota_store` to `buyer` together with the stores below it that
+/// `seller` holds and, `with_blocks`, the blocks `seller` owns there, all
+/// given in `subtree` as for `hand_down_store_subtree`. Nothing stays with
+/// `seller` once the price is paid; trees too large for one transaction
+/// change hands through `transfer_store` instead.
+pub fn sell_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut seller: SeahorseSigner<'info, '_>,
+    mut buyer: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut subtree: &'info [AccountInfo<'info>],
+    mut max_price: u64,
+    mut min_price: u64,
+    mut with_blocks: bool,
+) -> () {
+    if !(hota_store.borrow().price > 0) {
+        panic!("Store not for sale");
+    }
+
+    if !(seller.key() == hota_store.borrow().authority) {
+        panic!("Authority of store must be signer");
+    }
+
+    check_price_limits(hota_store.borrow().price, max_price, min_price);
+
+    solana_program::program::invoke(
+        &solana_program::system_instruction::transfer(
+            &buyer.key(),
+            &seller.key(),
+            hota_store.borrow().price,
+        ),
+        &[
+            buyer.to_account_info(),
+            seller.to_account_info(),
+            buyer.programs.get("system_program").clone(),
+        ],
+    )
+    .unwrap();
+
+    if !(hota_store.borrow().transfer_from == Pubkey::default()) {
+        panic!("Store subtree is still being handed down");
+    }
+
+    set_store_authority(&hota_store, buyer.key());
+
+    let mut accounts = subtree.iter();
+
+    hand_down_store_subtree(
+        &hota_store,
+        seller.key(),
+        buyer.key(),
+        with_blocks,
+        &mut accounts,
+        buyer.programs,
+    );
+
+    if !(accounts.next().is_none()) {
+        panic!("Accounts must follow the store subtree depth first");
+    }
+
+    assign!(hota_store.borrow_mut().price, 0);
+}
+
+/// Moves `block` to `xyz`: releases its cell and store slot and, when
+/// `new_store` is given, places it into slot `store_index` of that store,
+/// which may be `old_store`. `authority` must be allowed to edit
+/// `new_store`.
+pub fn move_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut block_history: Option<Mutable<LoadedBlockHistory<'info, '_>>>,
+    mut old_cell: Mutable<LoadedBlockCell<'info, '_>>,
+    mut new_cell: Empty<Mutable<LoadedBlockCell<'info, '_>>>,
+    mut old_store: Option<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut new_store: Option<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+    mut xyz: XYZ,
+    mut expected_revision: u64,
+    mut store_index: u8,
+) -> () {
+    check_block_revision(&block, expected_revision);
+
+    if !(old_cell.borrow().block == block.borrow().__account__.key()) {
+        panic!("Block cell does not belong to block");
+    }
+
+    let price = block.borrow().price;
+    let data = block.borrow().data.borrow().clone();
+
+    check_block_edit(&block, owner_block.key(), &xyz, price, &data, false);
+
+    let mut new_cell = new_cell.account.clone();
+
+    assign!(new_cell.borrow_mut().world, block.borrow().world);
+
+    assign!(new_cell.borrow_mut().xyz, Mutable::<XYZ>::new(xyz.clone()));
+
+    assign!(
+        new_cell.borrow_mut().block,
+        block.borrow().__account__.key()
+    );
+
+    unplace_block(&old_store, &block);
+
+    commit_block_revision(&block, block_history, xyz, data);
+
+    if let Some(new_store) = new_store {
+        check_store_editor(&new_store, &parcel, authority.key());
+
+        if !((block_permissions(&block, owner_block.key()) & BLOCK_PERMISSION_PLACE) != 0) {
+            panic!("Owner of block or delegate with place permission must be signer");
+        }
+
+        place_block(&new_store, &block, path, store_index);
+    }
+}
+
+pub fn close_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut block_cell: Mutable<LoadedBlockCell<'info, '_>>,
+    mut block_meta: UncheckedAccount<'info>,
+    mut block_history: UncheckedAccount<'info>,
+    mut hota_store: Option<Mutable<LoadedHotaStore<'info, '_>>>,
+) -> () {
+    if !(owner_block.key() == block.borrow().owner) {
+        panic!("Owner of block much be signer");
+    }
+
+    // The meta address is fixed by seeds, so an existing meta can not be
+    // left out to get around its flags; it is closed along with the block
+    if block_meta.data_len() > 0 {
+        let meta = BlockMeta::try_deserialize(&mut &block_meta.try_borrow_data().unwrap()[..])
+            .expect("Account is not a block meta");
+
+        if !((meta.flags & BLOCK_META_FLAG_INDESTRUCTIBLE) == 0) {
+            panic!("Block is indestructible");
+        }
+
+        close_program_account(&block_meta, &owner_block.to_account_info());
+    }
+
+    if !(block_cell.borrow().block == block.borrow().__account__.key()) {
+        panic!("Block cell does not belong to block");
+    }
+
+    if !(!block.borrow().frozen) {
+        panic!("Block is frozen");
+    }
+
+    if !(!block_lease_active(&block)) {
+        panic!("Block can not be closed while leased");
+    }
+
+    // Like the meta, the history is fixed by seeds and would otherwise
+    // outlive the block with its rent
+    if block_history.data_len() > 0 {
+        close_program_account(&block_history, &owner_block.to_account_info());
+    }
+
+    unplace_block(&hota_store, &block);
+}
+
+pub fn detach_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut parent: Mutable<LoadedHotaStore<'info, '_>>,
+    mut child: Mutable<LoadedHotaStore<'info, '_>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+) -> () {
+    check_store_editor(&parent, &parcel, authority.key());
+
+    unlink_store(&parent, &child);
+}
+
+/// Moves the subtree of `child` from `old_parent` into slot `index` of
+/// `new_parent`, which must have the same depth and lie in the same parcel
+/// and world, see `check_store_world` for `path`. `child_authority` must be
+/// allowed to edit `child`.
+pub fn relink_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut child_authority: SeahorseSigner<'info, '_>,
+    mut old_parent: Mutable<LoadedHotaStore<'info, '_>>,
+    mut new_parent: Mutable<LoadedHotaStore<'info, '_>>,
+    mut child: Mutable<LoadedHotaStore<'info, '_>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+    mut index: u8,
+) -> () {
+    check_store_editor(&old_parent, &parcel, authority.key());
+
+    check_store_editor(&new_parent, &parcel, authority.key());
+
+    check_store_editor(&child, &parcel, child_authority.key());
+
+    if !(new_parent.borrow().__account__.key() != old_parent.borrow().__account__.key()) {
+        panic!("New parent must differ from the old parent");
+    }
+
+    if !(new_parent.borrow().depth == old_parent.borrow().depth) {
+        panic!("New parent must have the depth of the old parent");
+    }
+
+    if !(new_parent.borrow().parcel == child.borrow().parcel) {
+        panic!("New parent belongs to another parcel");
+    }
+
+    check_store_world(&new_parent, &child, path);
+
+    unlink_store(&old_parent, &child);
+
+    link_store(&new_parent, &child, index);
+}
+
+/// Closes an empty, unlinked store. The rent goes to its creator, or to any
+/// `receiver` when the creator is the signing authority. `world` and
+/// `parcel` are the `World` and `Parcel` addresses of the store, which must
+/// be empty: a world root or a parcel store stays open.
+pub fn close_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut receiver: UncheckedAccount<'info>,
+    mut world: UncheckedAccount<'info>,
+    mut parcel: UncheckedAccount<'info>,
+) -> () {
+    check_store_authority(&hota_store, authority.key());
+
+    if !(world.data_len() == 0) {
+        panic!("World root stores can not be closed");
+    }
+
+    if !(parcel.data_len() == 0) {
+        panic!("Parcel stores can not be closed");
+    }
+
+    if !(hota_store.borrow().child_count == 0
+        && hota_store
+            .borrow()
+            .occupancy
+            .borrow()
+            .iter()
+            .all(|byte| *byte == 0))
+    {
+        panic!("Only empty stores can be closed");
+    }
+
+    // Slots and children of lazy stores may exist before they are counted
+    if !(!hota_store.borrow().lazy) {
+        panic!("Lazy stores can not be closed");
+    }
+
+    if !(hota_store.borrow().parent == Pubkey::default()) {
+        panic!("Store must be detached from its parent before closing");
+    }
+
+    if !(receiver.key() == hota_store.borrow().creator
+        || authority.key() == hota_store.borrow().creator)
+    {
+        panic!("Rent of store must go to its creator");
+    }
+}
+
+/// Slots of a spatial store kind along each axis.
+pub fn store_kind_split(kind: u8) -> XYZ {
+    if kind == STORE_KIND_OCTREE {
+        return XYZ { x: 2, y: 2, z: 2 };
+    }
+
+    if kind == STORE_KIND_SPATIAL_32 {
+        return XYZ { x: 4, y: 4, z: 2 };
+    }
+
+    panic!("Store kind has no spatial layout");
+}
+
+pub fn store_kind_fan_out(kind: u8) -> u16 {
+    let split = store_kind_split(kind);
+
+    return (split.x * split.y * split.z) as u16;
+}
+
+/// Slot of the spatial store covering `origin` + `edge` that contains `xyz`.
+pub fn spatial_store_slot(kind: u8, origin: &XYZ, edge: &XYZ, xyz: &XYZ) -> u8 {
+    let split = store_kind_split(kind);
+    // Widened so the offsets can not overflow near the ends of the i64 range
+    let dx = xyz.x as i128 - origin.x as i128;
+    let dy = xyz.y as i128 - origin.y as i128;
+    let dz = xyz.z as i128 - origin.z as i128;
+
+    if !(dx >= 0
+        && dy >= 0
+        && dz >= 0
+        && dx < edge.x as i128
+        && dy < edge.y as i128
+        && dz < edge.z as i128)
+    {
+        panic!("Coordinate is outside of the store");
+    }
+
+    let dx = dx as i64 / (edge.x / split.x);
+    let dy = dy as i64 / (edge.y / split.y);
+    let dz = dz as i64 / (edge.z / split.z);
+
+    return (dx + dy * split.x + dz * split.x * split.y) as u8;
+}
+
+/// Origin and edge of the child in slot `index` of a spatial store.
+pub fn child_store_box(kind: u8, origin: &XYZ, edge: &XYZ, index: u8) -> (XYZ, XYZ) {
+    let split = store_kind_split(kind);
+    let index = index as i64;
+
+    if !(index < split.x * split.y * split.z) {
+        panic!("Index must be < fan out of store");
+    }
+
+    let edge = XYZ {
+        x: edge.x / split.x,
+        y: edge.y / split.y,
+        z: edge.z / split.z,
+    };
+    let origin = XYZ {
+        x: origin.x + (index % split.x) * edge.x,
+        y: origin.y + (index / split.x % split.y) * edge.y,
+        z: origin.z + (index / (split.x * split.y)) * edge.z,
+    };
+
+    return (origin, edge);
+}
+
+/// Creates a root store of a spatial `kind` covering
+/// `split ^ depth` blocks per axis from `origin`, which must be aligned to
+/// that extent.
+pub fn init_spatial_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Empty<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut depth: u8,
+    mut kind: u8,
+    mut origin: XYZ,
+    mut seed_random: u128,
+) -> () {
+    let split = store_kind_split(kind);
+    let fan_out = store_kind_fan_out(kind);
+    let edge = XYZ {
+        x: split
+            .x
+            .checked_pow(depth as u32)
+            .expect("Store depth is too large"),
+        y: split
+            .y
+            .checked_pow(depth as u32)
+            .expect("Store depth is too large"),
+        z: split
+            .z
+            .checked_pow(depth as u32)
+            .expect("Store depth is too large"),
+    };
+
+    if !(origin.x.rem_euclid(edge.x) == 0
+        && origin.y.rem_euclid(edge.y) == 0
+        && origin.z.rem_euclid(edge.z) == 0)
+    {
+        panic!("Origin of store must be aligned to its edge");
+    }
+
+    init_store_handler(
+        payer,
+        authority,
+        hota_store.clone(),
+        depth,
+        fan_out,
+        seed_random,
+    );
+
+    let mut hota_store = hota_store.account.clone();
+
+    assign!(hota_store.borrow_mut().kind, kind);
+
+    assign!(hota_store.borrow_mut().origin, Mutable::<XYZ>::new(origin));
+
+    assign!(hota_store.borrow_mut().edge, Mutable::<XYZ>::new(edge));
+}
+
+/// Switches `hota_store` to lazy slot claims for good. Claims are only
+/// taken for blocks of `world`, which `path` from the world root down to
+/// the store proves unless the store has recorded it already.
+pub fn set_store_lazy_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut path: &[AccountInfo<'info>],
+    mut world: Pubkey,
+) -> () {
+    check_store_authority(&hota_store, authority.key());
+
+    if hota_store.borrow().world != world {
+        check_store_path(world, path, &hota_store);
+
+        set_store_path_world(world, path, &hota_store);
+    }
+
+    assign!(hota_store.borrow_mut().lazy, true);
+
+    // Children of lazy stores are only found at their derived address
+    assign!(hota_store.borrow_mut().derived, true);
+}
+
+/// Places `block` into slot `index` of the lazy `hota_store`, which is only
+/// read, so builders of one region do not contend for it.
+pub fn claim_store_slot_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+    mut store_slot: Empty<Mutable<LoadedStoreSlot<'info, '_>>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+    mut index: u8,
+) -> () {
+    check_store_editor(&hota_store, &parcel, authority.key());
+
+    if !(hota_store.borrow().lazy) {
+        panic!("Store is not lazy, use set_block_store");
+    }
+
+    if !((block_permissions(&block, owner_block.key()) & BLOCK_PERMISSION_PLACE) != 0) {
+        panic!("Owner of block or delegate with place permission must be signer");
+    }
+
+    check_store_slot_free(&hota_store, index);
+
+    check_block_slot(&hota_store, &block, path, index);
+
+    // Lazy stores are not written here, so their world is recorded up front
+    // by `set_store_lazy`
+    if !(hota_store.borrow().world == block.borrow().world) {
+        panic!("Store belongs to another world");
+    }
+
+    let mut store_slot = store_slot.account.clone();
+
+    assign!(
+        store_slot.borrow_mut().store,
+        hota_store.borrow().__account__.key()
+    );
+
+    assign!(store_slot.borrow_mut().index, index);
+
+    assign!(
+        store_slot.borrow_mut().child,
+        block.borrow().__account__.key()
+    );
+
+    assign!(
+        block.borrow_mut().store,
+        hota_store.borrow().__account__.key()
+    );
+
+    assign!(block.borrow_mut().store_index, index);
+}
+
+/// Creates the store in slot `index` of the lazy `parent`, which is only
+/// read; the child address proves the membership until it is synced.
+pub fn init_lazy_child_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut authority: SeahorseSigner<'info, '_>,
+    mut parent: Mutable<LoadedHotaStore<'info, '_>>,
+    mut child: Empty<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut parcel: Option<Mutable<LoadedParcel<'info, '_>>>,
+    mut index: u8,
+) -> () {
+    check_store_editor(&parent, &parcel, authority.key());
+
+    if !(parent.borrow().lazy) {
+        panic!("Store is not lazy, use init_child_store");
+    }
+
+    check_store_slot_free(&parent, index);
+
+    let mut child = child.account.clone();
+
+    init_child_store_fields(payer.key(), &parent, &child, index);
+
+    assign!(child.borrow_mut().parent, parent.borrow().__account__.key());
+
+    assign!(child.borrow_mut().parent_index, index);
+}
+
+/// Folds lazily claimed slots into `hota_store`. `members` are child stores
+/// created by `init_lazy_child_store` or `StoreSlot` accounts of
+/// `claim_store_slot`. Anyone can sync.
+pub fn sync_store_slots_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut members: &[AccountInfo<'info>],
+) -> () {
+    let store_key = hota_store.borrow().__account__.key();
+
+    for info in members.iter() {
+        let (index, child, address) = if hota_store.borrow().depth > 1 {
+            let child = read_store_account(info);
+
+            if !(child.parent == store_key) {
+                panic!("Store is not a child of the given parent");
+            }
+
+            let (address, _) = Pubkey::find_program_address(
+                &[
+                    "store".as_bytes(),
+                    store_key.as_ref(),
+                    &[child.parent_index],
+                ],
+                &id(),
+            );
+
+            (child.parent_index, info.key(), address)
+        } else {
+            if !(info.owner == &id()) {
+                panic!("Store slot account is not owned by the program");
+            }
+
+            let slot = StoreSlot::try_deserialize(&mut &info.try_borrow_data().unwrap()[..])
+                .expect("Account is not a store slot");
+
+            if !(slot.store == store_key) {
+                panic!("Store slot does not belong to store");
+            }
+
+            let (address, _) = Pubkey::find_program_address(
+                &[store_key.as_ref(), "slot".as_bytes(), &[slot.index]],
+                &id(),
+            );
+
+            (slot.index, slot.child, address)
+        };
+
+        if !(address == info.key()) {
+            panic!("Account is not a lazy member of the store");
+        }
+
+        if store_slot_occupied(&hota_store, index) {
+            if !(store_child(&hota_store, index) == child) {
+                panic!("Store slot holds another account");
+            }
+
+            continue;
+        }
+
+        set_store_child(&hota_store, index, child);
+
+        index_assign!(
+            hota_store.borrow_mut().occupancy.borrow_mut(),
+            (index / 8) as usize,
+            hota_store.borrow().occupancy.borrow()[(index / 8) as usize] | (1 << (index % 8))
+        );
+
+        assign!(
+            hota_store.borrow_mut().child_count,
+            hota_store.borrow().child_count + 1
+        );
+    }
+}
+
+/// Takes `block` out of its slot of the lazy `hota_store` and closes the
+/// claim, so the slot can be claimed again.
+pub fn vacate_store_slot_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner_block: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut store_slot: Mutable<LoadedStoreSlot<'info, '_>>,
+    mut block: Mutable<LoadedBlock<'info, '_>>,
+) -> () {
+    if !((block_permissions(&block, owner_block.key()) & BLOCK_PERMISSION_PLACE) != 0) {
+        panic!("Owner of block or delegate with place permission must be signer");
+    }
+
+    if !(store_slot.borrow().store == hota_store.borrow().__account__.key()
+        && store_slot.borrow().child == block.borrow().__account__.key()
+        && block.borrow().store == hota_store.borrow().__account__.key())
+    {
+        panic!("Store slot does not hold the block");
+    }
+
+    let index = store_slot.borrow().index;
+
+    if store_slot_occupied(&hota_store, index) {
+        release_store_slot(&hota_store, index, block.borrow().__account__.key());
+    }
+
+    assign!(block.borrow_mut().store, Pubkey::default());
+
+    assign!(block.borrow_mut().store_index, 0);
+}
+
+/// Leaf of slot `index` holding `child` in the content tree of a store.
+pub fn store_slot_leaf(index: u8, child: Pubkey, hash: &[u8; 32]) -> [u8; 32] {
+    return solana_program::hash::hashv(&[&[index], child.as_ref(), &hash[..]]).to_bytes();
+}
+
+/// Root of the binary Merkle tree over `leaves`, a power of two of them,
+/// each node `hash(left || right)`.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| solana_program::hash::hashv(&[&pair[0][..], &pair[1][..]]).to_bytes())
+            .collect();
+    }
+
+    return level[0];
+}
+
+/// Reads `content_hash` of the store (`is_store`) or block behind a
+/// remaining account at its fixed offset, without deserializing the rest.
+pub fn read_content_hash<'info>(info: &AccountInfo<'info>, is_store: bool) -> [u8; 32] {
+    if !(info.owner == &id()) {
+        panic!("Child account is not owned by the program");
+    }
+
+    let data = info.try_borrow_data().unwrap();
+    let (discriminator, version, offset) = if is_store {
+        (
+            <HotaStore as anchor_lang::Discriminator>::DISCRIMINATOR,
+            STORE_VERSION,
+            HotaStore::CONTENT_HASH_OFFSET,
+        )
+    } else {
+        (
+            <Block as anchor_lang::Discriminator>::DISCRIMINATOR,
+            BLOCK_VERSION,
+            Block::CONTENT_HASH_OFFSET,
+        )
+    };
+
+    if !(data.len() >= offset + 32 && data[..8] == discriminator) {
+        panic!("Account is not a child of the store");
+    }
+
+    let space = if is_store {
+        HotaStore::space(u16::from_le_bytes([data[10], data[11]]))
+    } else {
+        Block::SPACE
+    };
+
+    if !(data[8] == version && data.len() == space) {
+        panic!("Child must be migrated first");
+    }
+
+    return data[offset..offset + 32].try_into().unwrap();
+}
+
+/// Recomputes the hashes of the chunks of 8 slots covering `start..end` of
+/// `hota_store`, then its content hash over all chunks. `children` are the
+/// occupied children in that range in slot order: blocks for a depth 1
+/// store, child stores otherwise. Other chunks keep their last hash, so
+/// large stores can be refreshed over several calls. Child stores should be
+/// refreshed first. Anyone can refresh.
+pub fn refresh_store_hash_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut children: &[AccountInfo<'info>],
+    mut start: u16,
+    mut end: u16,
+) -> () {
+    let fan_out = hota_store.borrow().fan_out as usize;
+    let (start, end) = (start as usize, end as usize);
+
+    if !(start % 8 == 0 && start < end && end <= fan_out && (end % 8 == 0 || end == fan_out)) {
+        panic!("Hash refresh must cover whole chunks of 8 slots");
+    }
+
+    let is_store = hota_store.borrow().depth > 1;
+    let mut next = 0;
+
+    for chunk in (start / 8)..((end + 7) / 8) {
+        let mut leaves = [[0u8; 32]; 8];
+        let mut occupied = false;
+
+        for offset in 0..8 {
+            let index = chunk * 8 + offset;
+
+            if index >= fan_out || !store_slot_occupied(&hota_store, index as u8) {
+                continue;
+            }
+
+            if !(next < children.len()
+                && children[next].key() == store_child(&hota_store, index as u8))
+            {
+                panic!("Every child in the range must be given in slot order");
+            }
+
+            let hash = read_content_hash(&children[next], is_store);
+
+            leaves[offset] = store_slot_leaf(index as u8, children[next].key(), &hash);
+            occupied = true;
+            next += 1;
+        }
+
+        let hash = if occupied {
+            merkle_root(&leaves)
+        } else {
+            [0; 32]
+        };
+
+        index_assign!(
+            hota_store.borrow_mut().chunk_hashes.borrow_mut(),
+            chunk,
+            hash
+        );
+    }
+
+    if !(next == children.len()) {
+        panic!("Account is not a child of the store");
+    }
+
+    let content_hash = if hota_store.borrow().child_count == 0 {
+        [0; 32]
+    } else {
+        merkle_root(&hota_store.borrow().chunk_hashes.borrow()[..])
+    };
+
+    assign!(
+        hota_store.borrow_mut().content_hash,
+        Mutable::<[u8; 32]>::new(content_hash)
+    );
+
+    assign!(
+        hota_store.borrow_mut().hashed_at,
+        Clock::get().unwrap().slot
+    );
+}
+
+/// Most frequent non-air material among `materials`, 0 when all are air.
+pub fn dominant_material(materials: &[u8]) -> u8 {
+    let mut counts = [0u16; 256];
+
+    for material in materials.iter() {
+        counts[*material as usize] += 1;
+    }
+
+    let mut dominant = 0;
+
+    for material in 1..256 {
+        if counts[material] > counts[dominant] || (dominant == 0 && counts[material] > 0) {
+            dominant = material;
+        }
+    }
+
+    return dominant as u8;
+}
+
+/// Recomputes the level of detail summary of slots `start..end` of
+/// `hota_store`. `children` are the occupied children in that range in slot
+/// order, blocks for a depth 1 store and child stores otherwise; free slots
+/// in the range are cleared. Slots outside it are left as they are, so large
+/// stores can be refreshed in chunks. Anyone can refresh.
+pub fn refresh_store_lod_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut hota_store: Mutable<LoadedHotaStore<'info, '_>>,
+    mut children: &[AccountInfo<'info>],
+    mut start: u16,
+    mut end: u16,
+) -> () {
+    if !(start <= end && end <= hota_store.borrow().fan_out) {
+        panic!("Refresh range must lie within the fan out of the store");
+    }
+
+    let mut next = 0;
+
+    for slot in (start..end).map(|slot| slot as u8) {
+        let (material, solid) = if !store_slot_occupied(&hota_store, slot) {
+            (0, false)
+        } else {
+            if !(next < children.len() && children[next].key() == store_child(&hota_store, slot)) {
+                panic!("Every child in the range must be given in slot order");
+            }
+
+            let summary = if hota_store.borrow().depth > 1 {
+                let child = read_store_account(&children[next]);
+
+                (
+                    dominant_material(&read_store_materials(&children[next], child.fan_out)),
+                    child.lod_solid.iter().any(|byte| *byte != 0),
+                )
+            } else {
+                let block = read_block_account(&children[next]);
+
+                (
+                    dominant_material(&block.data),
+                    block.data.iter().any(|voxel| *voxel != 0),
+                )
+            };
+
+            next += 1;
+
+            summary
+        };
+
+        set_store_slot_lod(&hota_store, slot, material, solid);
+    }
+
+    if !(next == children.len()) {
+        panic!("Account is not a child of the store");
+    }
+}
+
+/// Index of the occupied slot of `store` holding `child`.
+pub fn store_slot_of<'info>(
+    store: &Mutable<LoadedHotaStore<'info, '_>>,
+    child: Pubkey,
+) -> Option<u8> {
+    return (0..store.borrow().fan_out)
+        .map(|index| index as u8)
+        .find(|index| store_slot_occupied(store, *index) && store_child(store, *index) == child);
+}
+
+/// Rewrites a block created before `Block::version` existed to the newest
+/// layout, claiming its coordinate in `world` through `block_cell`; its
+/// owner must sign. A block held by a store is migrated after that store,
+/// given as `hota_store` with `path` from `world` down to it, and takes the
+/// slot that store kept for it. Migrated without a store, the block is not
+/// placed, and a slot kept for it stays free. Every other new field starts
+/// empty.
+pub fn migrate_block_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut owner: SeahorseSigner<'info, '_>,
+    mut block: UncheckedAccount<'info>,
+    mut world: Pubkey,
+    mut block_cell: UncheckedAccount<'info>,
+    mut hota_store: Option<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut path: &[AccountInfo<'info>],
+) -> () {
+    let info = block.to_account_info();
+
+    if !(info.owner == &id()) {
+        panic!("Block account is not owned by the program");
+    }
+
+    let data = info.try_borrow_data().unwrap().to_vec();
+
+    if !(data.len() >= 9 && data[..8] == <Block as anchor_lang::Discriminator>::DISCRIMINATOR) {
+        panic!("Account is not a block");
+    }
+
+    if data.len() == Block::SPACE && data[8] == BLOCK_VERSION {
+        panic!("Block is already at the current version");
+    }
+
+    if !(data.len() == BLOCK_LEGACY_SPACE) {
+        panic!("Unknown block layout");
+    }
+
+    if !(owner.key() == Pubkey::try_from(&data[8..40]).unwrap()) {
+        panic!("Owner of block much be signer");
+    }
+
+    let xyz = XYZ {
+        x: i64::from_le_bytes(data[40..48].try_into().unwrap()),
+        y: i64::from_le_bytes(data[48..56].try_into().unwrap()),
+        z: i64::from_le_bytes(data[56..64].try_into().unwrap()),
+    };
+    let price = u64::from_le_bytes(data[64..72].try_into().unwrap());
+    let voxels: [u8; 512] = data[72..584].try_into().unwrap();
+    let block_key = info.key();
+
+    let (store, store_index) = match &hota_store {
+        Some(hota_store) => {
+            if !(hota_store.borrow().depth == 1) {
+                panic!("Store much have depth = 1");
+            }
+
+            check_store_path(world, path, hota_store);
+
+            set_store_path_world(world, path, hota_store);
+
+            // `migrate_store` keeps the slots of blocks free until they are
+            // migrated, so a block is only ever placed once
+            let index = (0..hota_store.borrow().fan_out)
+                .map(|index| index as u8)
+                .find(|index| {
+                    !store_slot_occupied(hota_store, *index)
+                        && store_child(hota_store, *index) == block_key
+                })
+                .expect("Store does not keep a slot for the block");
+
+            occupy_store_slot(hota_store, index, block_key);
+
+            (hota_store.borrow().__account__.key(), index)
+        }
+        None => (Pubkey::default(), 0),
+    };
+
+    let (address, bump) = Pubkey::find_program_address(
+        &[
+            "cell".as_bytes(),
+            world.as_ref(),
+            &xyz.x.to_le_bytes(),
+            &xyz.y.to_le_bytes(),
+            &xyz.z.to_le_bytes(),
+        ],
+        &id(),
+    );
+
+    if !(address == block_cell.key()) {
+        panic!("Block cell does not match the block coordinate");
+    }
+
+    if !(block_cell.data_len() == 0) {
+        panic!("Coordinate of the block is already claimed in the world");
+    }
+
+    create_pda_account(
+        &payer,
+        &block_cell,
+        &[
+            "cell".as_bytes(),
+            world.as_ref(),
+            &xyz.x.to_le_bytes(),
+            &xyz.y.to_le_bytes(),
+            &xyz.z.to_le_bytes(),
+            &[bump],
+        ],
+        std::mem::size_of::<BlockCell>() + 8,
+    );
+
+    let cell = BlockCell {
+        world,
+        xyz: xyz.clone(),
+        block: block_key,
+    };
+
+    cell.try_serialize(&mut &mut block_cell.try_borrow_mut_data().unwrap()[..])
+        .unwrap();
+
+    let migrated = Block {
+        version: BLOCK_VERSION,
+        owner: owner.key(),
+        xyz: xyz.clone(),
+        price,
+        data: voxels,
+        delegates: [Pubkey::default(); BLOCK_MAX_DELEGATES],
+        delegate_permissions: [0; BLOCK_MAX_DELEGATES],
+        lease_rate: 0,
+        lease_max_slots: 0,
+        renter: Pubkey::default(),
+        lease_expiry: 0,
+        frozen: false,
+        revision: 0,
+        created_at: 0,
+        updated_at: 0,
+        content_hash: block_content_hash(&xyz, &voxels),
+        history_hash: [0; 32],
+        pending_owner: Pubkey::default(),
+        world,
+        store,
+        store_index,
+    };
+
+    drop(data);
+
+    resize_program_account(&payer, &info, Block::SPACE);
+
+    write_block_account(&info, &migrated);
+}
+
+/// Rewrites a store created before `HotaStore::version` existed to the
+/// newest layout, keeping its depth and occupied slots. The slots of a depth
+/// 1 store are kept free for their blocks until `migrate_block` places
+/// them. A child store is migrated after `parent`, which must hold it and
+/// passes on its authority, parcel and creator. Old stores had no owner, so
+/// a root goes to `authority`, who must be the upgrade authority of the
+/// program given by `program_data`, and refunds its rent to them.
+/// Flat coordinates keep their `COORDINATE_MAPPING_POSITIVE` layout, every
+/// other new field starts empty.
+pub fn migrate_store_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut hota_store: UncheckedAccount<'info>,
+    mut parent: Option<Mutable<LoadedHotaStore<'info, '_>>>,
+    mut authority: Option<SeahorseSigner<'info, '_>>,
+    mut program_data: Option<Box<Account<'info, ProgramData>>>,
+) -> () {
+    let info = hota_store.to_account_info();
+
+    if !(info.owner == &id()) {
+        panic!("Store account is not owned by the program");
+    }
+
+    let data = info.try_borrow_data().unwrap().to_vec();
+
+    if !(data.len() >= 12 && data[..8] == <HotaStore as anchor_lang::Discriminator>::DISCRIMINATOR)
+    {
+        panic!("Account is not a store");
+    }
+
+    let fan_out = u16::from_le_bytes([data[10], data[11]]);
+
+    if data.len() == HotaStore::space(fan_out) && data[8] == STORE_VERSION {
+        panic!("Store is already at the current version");
+    }
+
+    if !(data.len() == STORE_LEGACY_SPACE) {
+        panic!("Unknown store layout");
+    }
+
+    let depth = data[8];
+    let fan_out = STORE_LEGACY_FAN_OUT as usize;
+    let status = &data[9 + 32 * fan_out..];
+    let mut occupancy = [0u8; STORE_BITMAP_LEN];
+    let mut child_count = 0;
+
+    for index in 0..fan_out {
+        if status[index] != 0 && depth > 1 {
+            occupancy[index / 8] |= 1 << (index % 8);
+            child_count += 1;
+        }
+    }
+
+    let store_key = info.key();
+    let (authority, parcel, creator, parent_key, parent_index) = match &parent {
+        Some(parent) => {
+            if !(parent.borrow().depth == depth + 1) {
+                panic!("high node much have depth = low node depth + 1");
+            }
+
+            let index = store_slot_of(parent, store_key).expect("Parent does not hold the store");
+
+            (
+                parent.borrow().authority,
+                parent.borrow().parcel,
+                parent.borrow().creator,
+                parent.borrow().__account__.key(),
+                index,
+            )
+        }
+        None => {
+            let authority = authority.expect("Authority of a root store must be given");
+            let program_data = program_data.expect("Program data must be given for a root store");
+            let program_data_key = Pubkey::find_program_address(
+                &[id().as_ref()],
+                &solana_program::bpf_loader_upgradeable::id(),
+            )
+            .0;
+
+            if !(program_data.key() == program_data_key) {
+                panic!("Program data does not belong to the program");
+            }
+
+            if !(program_data.upgrade_authority_address == Some(authority.key())) {
+                panic!("Upgrade authority of the program must be signer");
+            }
+
+            (
+                authority.key(),
+                Pubkey::default(),
+                authority.key(),
+                Pubkey::default(),
+                0,
+            )
+        }
+    };
+
+    let migrated = HotaStore {
+        version: STORE_VERSION,
+        depth,
+        fan_out: STORE_LEGACY_FAN_OUT,
+        child_count,
+        occupancy,
+        authority,
+        delegates: [Pubkey::default(); STORE_MAX_DELEGATES],
+        parcel,
+        world: Pubkey::default(),
+        price: 0,
+        parent: parent_key,
+        parent_index,
+        creator,
+        kind: STORE_KIND_FLAT,
+        origin: XYZ::default(),
+        edge: XYZ::default(),
+        mapping: COORDINATE_MAPPING_POSITIVE,
+        lazy: false,
+        derived: false,
+        transfer_from: Pubkey::default(),
+        transfer_blocks: false,
+        transfer_cursor: 0,
+        content_hash: [0; 32],
+        chunk_hashes: [[0; 32]; STORE_BITMAP_LEN],
+        hashed_at: 0,
+        lod_solid: [0; STORE_BITMAP_LEN],
+    };
+
+    resize_program_account(&payer, &info, HotaStore::space(STORE_LEGACY_FAN_OUT));
+
+    write_store_account(&info, &migrated);
+
+    init_store_slots(&info, STORE_LEGACY_FAN_OUT);
+
+    for index in 0..fan_out {
+        if status[index] != 0 {
+            let child = Pubkey::try_from(&data[9 + 32 * index..9 + 32 * (index + 1)]).unwrap();
+
+            write_store_child(&info, STORE_LEGACY_FAN_OUT, index as u8, child);
+        }
+    }
 }
@@ -169,20 +169,26 @@ mod electra_chain {
     use std::collections::HashMap;
 
     #[derive(Accounts)]
-    # [instruction (xyz: XYZ , price : u64 , data: [u8; 512] , seed_random : u128)]
+    # [instruction (xyz : XYZ , price : u64 , data : [u8; 512] , seed_random : u128)]
     pub struct InitBlock<'info> {
         #[account(mut)]
         pub payer: Signer<'info>,
         #[account(mut)]
         pub owner: Signer<'info>,
-        # [account (init , space = std :: mem :: size_of :: < dot :: program :: Block > () + 8 , payer = payer , seeds = [owner . key () . as_ref () , "block" . as_bytes () . as_ref () , seed_random . to_le_bytes () . as_ref ()] , bump)]
+        pub world: Box<Account<'info, dot::program::HotaStore>>,
+        # [account (seeds = ["world" . as_bytes () . as_ref () , world . key () . as_ref ()] , bump)]
+        pub world_settings: Box<Account<'info, dot::program::World>>,
+        # [account (init , space = dot :: program :: Block :: SPACE , payer = payer , seeds = [owner . key () . as_ref () , "block" . as_bytes () . as_ref () , seed_random . to_le_bytes () . as_ref ()] , bump)]
         pub block: Box<Account<'info, dot::program::Block>>,
+        # [account (init , space = std :: mem :: size_of :: < dot :: program :: BlockCell > () + 8 , payer = payer , seeds = ["cell" . as_bytes () . as_ref () , world . key () . as_ref () , xyz . x . to_le_bytes () . as_ref () , xyz . y . to_le_bytes () . as_ref () , xyz . z . to_le_bytes () . as_ref ()] , bump)]
+        pub block_cell: Box<Account<'info, dot::program::BlockCell>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
         pub rent: Sysvar<'info, Rent>,
         pub system_program: Program<'info, System>,
     }
 
-    pub fn init_block(
-        ctx: Context<InitBlock>,
+    pub fn init_block<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitBlock<'info>>,
         xyz: XYZ,
         price: u64,
         data: [u8; 512],
@@ -206,15 +212,36 @@ mod electra_chain {
             programs: &programs_map,
         };
 
+        let world_settings =
+            dot::program::World::load(&mut ctx.accounts.world_settings, &programs_map);
+
+        ctx.accounts.block.version = dot::program::BLOCK_VERSION;
+
         let block = Empty {
             account: dot::program::Block::load(&mut ctx.accounts.block, &programs_map),
             bump: Some(ctx.bumps.block),
         };
 
+        let block_cell = Empty {
+            account: dot::program::BlockCell::load(&mut ctx.accounts.block_cell, &programs_map),
+            bump: Some(ctx.bumps.block_cell),
+        };
+
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
         init_block_handler(
             payer.clone(),
             owner.clone(),
+            ctx.accounts.world.key(),
+            world_settings.clone(),
             block.clone(),
+            block_cell.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
             xyz,
             price,
             data,
@@ -223,21 +250,30 @@ mod electra_chain {
 
         dot::program::Block::store(block.account);
 
+        dot::program::BlockCell::store(block_cell.account);
+
         return Ok(());
     }
 
     #[derive(Accounts)]
-    # [instruction (depth : u8 , seed_random : u128)]
+    # [instruction (depth : u8 , fan_out : u16 , seed_random : u128)]
     pub struct InitStore<'info> {
         #[account(mut)]
         pub payer: Signer<'info>,
-        # [account (init , space = std :: mem :: size_of :: < dot :: program :: HotaStore > () + 8 , payer = payer , seeds = ["store" . as_bytes () . as_ref () , depth . to_le_bytes () . as_ref () , seed_random . to_le_bytes () . as_ref ()] , bump)]
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        # [account (init , space = dot :: program :: HotaStore :: space (fan_out) , payer = payer , seeds = ["store" . as_bytes () . as_ref () , depth . to_le_bytes () . as_ref () , seed_random . to_le_bytes () . as_ref ()] , bump)]
         pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
         pub rent: Sysvar<'info, Rent>,
         pub system_program: Program<'info, System>,
     }
 
-    pub fn init_store(ctx: Context<InitStore>, depth: u8, seed_random: u128) -> Result<()> {
+    pub fn init_store(
+        ctx: Context<InitStore>,
+        depth: u8,
+        fan_out: u16,
+        seed_random: u128,
+    ) -> Result<()> {
         let mut programs = HashMap::new();
 
         programs.insert(
@@ -251,12 +287,27 @@ mod electra_chain {
             programs: &programs_map,
         };
 
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        ctx.accounts.hota_store.version = dot::program::STORE_VERSION;
+        ctx.accounts.hota_store.fan_out = fan_out;
+
         let hota_store = Empty {
             account: dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map),
             bump: Some(ctx.bumps.hota_store),
         };
 
-        init_store_handler(payer.clone(), hota_store.clone(), depth, seed_random);
+        init_store_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            depth,
+            fan_out,
+            seed_random,
+        );
 
         dot::program::HotaStore::store(hota_store.account);
 
@@ -271,12 +322,18 @@ mod electra_chain {
         #[account(mut)]
         pub owner_block: Signer<'info>,
         #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
         pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
         #[account(mut)]
         pub block: Box<Account<'info, dot::program::Block>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
     }
 
-    pub fn set_block_store(ctx: Context<SetBlockStore>, index: u8) -> Result<()> {
+    pub fn set_block_store<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetBlockStore<'info>>,
+        index: u8,
+    ) -> Result<()> {
         let mut programs = HashMap::new();
         let programs_map = ProgramsMap(programs);
         let payer = SeahorseSigner {
@@ -289,14 +346,27 @@ mod electra_chain {
             programs: &programs_map,
         };
 
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
         let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
         let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
 
         set_block_store_handler(
             payer.clone(),
             owner_block.clone(),
+            authority.clone(),
             hota_store.clone(),
             block.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
             index,
         );
 
@@ -313,12 +383,20 @@ mod electra_chain {
         #[account(mut)]
         pub payer: Signer<'info>,
         #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub child_authority: Signer<'info>,
+        #[account(mut)]
         pub hi_store: Box<Account<'info, dot::program::HotaStore>>,
         #[account(mut)]
         pub lo_store: Box<Account<'info, dot::program::HotaStore>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
     }
 
-    pub fn set_ele_store(ctx: Context<SetEleStore>, index: u8) -> Result<()> {
+    pub fn set_ele_store<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetEleStore<'info>>,
+        index: u8,
+    ) -> Result<()> {
         let mut programs = HashMap::new();
         let programs_map = ProgramsMap(programs);
         let payer = SeahorseSigner {
@@ -326,10 +404,34 @@ mod electra_chain {
             programs: &programs_map,
         };
 
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let child_authority = SeahorseSigner {
+            account: &ctx.accounts.child_authority,
+            programs: &programs_map,
+        };
+
         let hi_store = dot::program::HotaStore::load(&mut ctx.accounts.hi_store, &programs_map);
         let lo_store = dot::program::HotaStore::load(&mut ctx.accounts.lo_store, &programs_map);
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
 
-        set_ele_store_handler(payer.clone(), hi_store.clone(), lo_store.clone(), index);
+        set_ele_store_handler(
+            payer.clone(),
+            authority.clone(),
+            child_authority.clone(),
+            hi_store.clone(),
+            lo_store.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+            index,
+        );
 
         dot::program::HotaStore::store(hi_store);
 
@@ -339,6 +441,7 @@ mod electra_chain {
     }
 
     #[derive(Accounts)]
+    # [instruction (max_price : u64 , min_price : u64)]
     pub struct TradeBlock<'info> {
         #[account(mut)]
         pub payer: Signer<'info>,
@@ -351,7 +454,7 @@ mod electra_chain {
         pub system_program: Program<'info, System>,
     }
 
-    pub fn trade_block(ctx: Context<TradeBlock>) -> Result<()> {
+    pub fn trade_block(ctx: Context<TradeBlock>, max_price: u64, min_price: u64) -> Result<()> {
         let mut programs = HashMap::new();
 
         programs.insert(
@@ -382,6 +485,8 @@ mod electra_chain {
             old_onwer.clone(),
             new_owner.clone(),
             block.clone(),
+            max_price,
+            min_price,
         );
 
         dot::program::Block::store(block);
@@ -390,7 +495,7 @@ mod electra_chain {
     }
 
     #[derive(Accounts)]
-    # [instruction (xyz: XYZ , price : u64 , data: [u8; 512])]
+    # [instruction (xyz: XYZ , price : u64 , data: [u8; 512] , expected_revision : u64)]
     pub struct UpdateBlock<'info> {
         #[account(mut)]
         pub payer: Signer<'info>,
@@ -398,6 +503,10 @@ mod electra_chain {
         pub owner_block: Signer<'info>,
         #[account(mut)]
         pub block: Box<Account<'info, dot::program::Block>>,
+        #[account(mut)]
+        pub block_history: Option<Box<Account<'info, dot::program::BlockHistory>>>,
+        # [account (seeds = [block . key () . as_ref () , "meta" . as_bytes () . as_ref ()] , bump)]
+        pub block_meta: Option<Box<Account<'info, dot::program::BlockMeta>>>,
     }
 
     pub fn update_block(
@@ -405,6 +514,7 @@ mod electra_chain {
         xyz: XYZ,
         price: u64,
         data: [u8; 512],
+        expected_revision: u64,
     ) -> Result<()> {
         let mut programs = HashMap::new();
         let programs_map = ProgramsMap(programs);
@@ -419,18 +529,2303 @@ mod electra_chain {
         };
 
         let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let block_history =
+            ctx.accounts.block_history.as_mut().map(|block_history| {
+                dot::program::BlockHistory::load(block_history, &programs_map)
+            });
+
+        let block_meta = ctx
+            .accounts
+            .block_meta
+            .as_mut()
+            .map(|block_meta| dot::program::BlockMeta::load(block_meta, &programs_map));
 
         update_block_handler(
             payer.clone(),
             owner_block.clone(),
             block.clone(),
+            block_history.clone(),
+            block_meta.clone(),
             xyz,
             price,
             data,
+            expected_revision,
+        );
+
+        dot::program::Block::store(block);
+
+        if let Some(block_history) = block_history {
+            dot::program::BlockHistory::store(block_history);
+        }
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (biome : u8 , light : u8 , flags : u8 , name : [u8 ; 32] , uri : [u8 ; 128])]
+    pub struct InitBlockMeta<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        # [account (init , space = std :: mem :: size_of :: < dot :: program :: BlockMeta > () + 8 , payer = payer , seeds = [block . key () . as_ref () , "meta" . as_bytes () . as_ref ()] , bump)]
+        pub block_meta: Box<Account<'info, dot::program::BlockMeta>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_block_meta(
+        ctx: Context<InitBlockMeta>,
+        biome: u8,
+        light: u8,
+        flags: u8,
+        name: [u8; 32],
+        uri: [u8; 128],
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let block_meta = Empty {
+            account: dot::program::BlockMeta::load(&mut ctx.accounts.block_meta, &programs_map),
+            bump: Some(ctx.bumps.block_meta),
+        };
+
+        init_block_meta_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            block_meta.clone(),
+            biome,
+            light,
+            flags,
+            name,
+            uri,
+        );
+
+        dot::program::Block::store(block);
+
+        dot::program::BlockMeta::store(block_meta.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (biome : u8 , light : u8 , flags : u8 , name : [u8 ; 32] , uri : [u8 ; 128])]
+    pub struct UpdateBlockMeta<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        #[account(mut)]
+        pub block_meta: Box<Account<'info, dot::program::BlockMeta>>,
+    }
+
+    pub fn update_block_meta(
+        ctx: Context<UpdateBlockMeta>,
+        biome: u8,
+        light: u8,
+        flags: u8,
+        name: [u8; 32],
+        uri: [u8; 128],
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let block_meta = dot::program::BlockMeta::load(&mut ctx.accounts.block_meta, &programs_map);
+
+        update_block_meta_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            block_meta.clone(),
+            biome,
+            light,
+            flags,
+            name,
+            uri,
+        );
+
+        dot::program::Block::store(block);
+
+        dot::program::BlockMeta::store(block_meta);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (delegate : Pubkey , permissions : u8)]
+    pub struct AddBlockDelegate<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn add_block_delegate(
+        ctx: Context<AddBlockDelegate>,
+        delegate: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        add_block_delegate_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            delegate,
+            permissions,
+        );
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (delegate : Pubkey)]
+    pub struct RevokeBlockDelegate<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn revoke_block_delegate(
+        ctx: Context<RevokeBlockDelegate>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        revoke_block_delegate_handler(payer.clone(), owner_block.clone(), block.clone(), delegate);
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (rate : u64 , max_slots : u64)]
+    pub struct OfferLease<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn offer_lease(ctx: Context<OfferLease>, rate: u64, max_slots: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        offer_lease_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            rate,
+            max_slots,
+        );
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (slots : u64 , max_rate : u64)]
+    pub struct RentBlock<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub renter: Signer<'info>,
+        #[account(mut)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub owner: UncheckedAccount<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn rent_block(ctx: Context<RentBlock>, slots: u64, max_rate: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let renter = SeahorseSigner {
+            account: &ctx.accounts.renter,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        rent_block_handler(
+            payer.clone(),
+            renter.clone(),
+            ctx.accounts.owner.clone(),
+            block.clone(),
+            slots,
+            max_rate,
         );
 
         dot::program::Block::store(block);
 
         return Ok(());
     }
+
+    #[derive(Accounts)]
+    pub struct FreezeBlock<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn freeze_block(ctx: Context<FreezeBlock>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        freeze_block_handler(payer.clone(), owner_block.clone(), block.clone());
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct InitBlockHistory<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        # [account (init , space = std :: mem :: size_of :: < dot :: program :: BlockHistory > () + 8 , payer = payer , seeds = [block . key () . as_ref () , "history" . as_bytes () . as_ref ()] , bump)]
+        pub block_history: Box<Account<'info, dot::program::BlockHistory>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_block_history(ctx: Context<InitBlockHistory>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let block_history = Empty {
+            account: dot::program::BlockHistory::load(
+                &mut ctx.accounts.block_history,
+                &programs_map,
+            ),
+            bump: Some(ctx.bumps.block_history),
+        };
+
+        init_block_history_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            block_history.clone(),
+        );
+
+        dot::program::Block::store(block);
+
+        dot::program::BlockHistory::store(block_history.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (revision : u64 , expected_revision : u64)]
+    pub struct RevertBlock<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        #[account(mut)]
+        pub block_history: Box<Account<'info, dot::program::BlockHistory>>,
+    }
+
+    pub fn revert_block(
+        ctx: Context<RevertBlock>,
+        revision: u64,
+        expected_revision: u64,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let block_history =
+            dot::program::BlockHistory::load(&mut ctx.accounts.block_history, &programs_map);
+
+        revert_block_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            block_history.clone(),
+            revision,
+            expected_revision,
+        );
+
+        dot::program::Block::store(block);
+
+        dot::program::BlockHistory::store(block_history);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (new_owner : Pubkey , require_accept : bool)]
+    pub struct TransferBlock<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn transfer_block(
+        ctx: Context<TransferBlock>,
+        new_owner: Pubkey,
+        require_accept: bool,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        transfer_block_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            new_owner,
+            require_accept,
+        );
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct AcceptBlockTransfer<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub new_owner: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn accept_block_transfer(ctx: Context<AcceptBlockTransfer>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let new_owner = SeahorseSigner {
+            account: &ctx.accounts.new_owner,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        accept_block_transfer_handler(payer.clone(), new_owner.clone(), block.clone());
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct CancelBlockTransfer<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn cancel_block_transfer(ctx: Context<CancelBlockTransfer>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        cancel_block_transfer_handler(payer.clone(), owner_block.clone(), block.clone());
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct SetBlockStoreAuto<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+    }
+
+    pub fn set_block_store_auto<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetBlockStoreAuto<'info>>,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        set_block_store_auto_handler(
+            payer.clone(),
+            owner_block.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            block.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct SetEleStoreAuto<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub child_authority: Signer<'info>,
+        #[account(mut)]
+        pub hi_store: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut)]
+        pub lo_store: Box<Account<'info, dot::program::HotaStore>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+    }
+
+    pub fn set_ele_store_auto<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetEleStoreAuto<'info>>,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let child_authority = SeahorseSigner {
+            account: &ctx.accounts.child_authority,
+            programs: &programs_map,
+        };
+
+        let hi_store = dot::program::HotaStore::load(&mut ctx.accounts.hi_store, &programs_map);
+        let lo_store = dot::program::HotaStore::load(&mut ctx.accounts.lo_store, &programs_map);
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        set_ele_store_auto_handler(
+            payer.clone(),
+            authority.clone(),
+            child_authority.clone(),
+            hi_store.clone(),
+            lo_store.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+        );
+
+        dot::program::HotaStore::store(hi_store);
+
+        dot::program::HotaStore::store(lo_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (delegate : Pubkey)]
+    pub struct AddStoreDelegate<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn add_store_delegate(ctx: Context<AddStoreDelegate>, delegate: Pubkey) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        add_store_delegate_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            delegate,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (delegate : Pubkey)]
+    pub struct RevokeStoreDelegate<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn revoke_store_delegate(
+        ctx: Context<RevokeStoreDelegate>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        revoke_store_delegate_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            delegate,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (new_authority : Pubkey)]
+    pub struct TransferStoreAuthority<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn transfer_store_authority(
+        ctx: Context<TransferStoreAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        transfer_store_authority_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            new_authority,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (treasury : Pubkey , parcel_fee : u64 , parcel_depth : u8)]
+    pub struct InitWorld<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub root_store: Box<Account<'info, dot::program::HotaStore>>,
+        # [account (init , space = std :: mem :: size_of :: < dot :: program :: World > () + 8 , payer = payer , seeds = ["world" . as_bytes () . as_ref () , root_store . key () . as_ref ()] , bump)]
+        pub world: Box<Account<'info, dot::program::World>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_world(
+        ctx: Context<InitWorld>,
+        treasury: Pubkey,
+        parcel_fee: u64,
+        parcel_depth: u8,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let root_store = dot::program::HotaStore::load(&mut ctx.accounts.root_store, &programs_map);
+        let world = Empty {
+            account: dot::program::World::load(&mut ctx.accounts.world, &programs_map),
+            bump: Some(ctx.bumps.world),
+        };
+
+        init_world_handler(
+            payer.clone(),
+            authority.clone(),
+            root_store.clone(),
+            world.clone(),
+            treasury,
+            parcel_fee,
+            parcel_depth,
+        );
+
+        dot::program::HotaStore::store(root_store);
+
+        dot::program::World::store(world.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (treasury : Pubkey , parcel_fee : u64)]
+    pub struct UpdateWorld<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub world: Box<Account<'info, dot::program::World>>,
+    }
+
+    pub fn update_world(
+        ctx: Context<UpdateWorld>,
+        treasury: Pubkey,
+        parcel_fee: u64,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let world = dot::program::World::load(&mut ctx.accounts.world, &programs_map);
+
+        update_world_handler(
+            payer.clone(),
+            authority.clone(),
+            world.clone(),
+            treasury,
+            parcel_fee,
+        );
+
+        dot::program::World::store(world);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct ClaimParcel<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub world: Box<Account<'info, dot::program::World>>,
+        #[account(mut)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub treasury: UncheckedAccount<'info>,
+        #[account(mut)]
+        pub parcel_store: Box<Account<'info, dot::program::HotaStore>>,
+        # [account (init , space = std :: mem :: size_of :: < dot :: program :: Parcel > () + 8 , payer = payer , seeds = ["parcel" . as_bytes () . as_ref () , parcel_store . key () . as_ref ()] , bump)]
+        pub parcel: Box<Account<'info, dot::program::Parcel>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn claim_parcel<'info>(ctx: Context<'_, '_, '_, 'info, ClaimParcel<'info>>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let world = dot::program::World::load(&mut ctx.accounts.world, &programs_map);
+        let parcel_store =
+            dot::program::HotaStore::load(&mut ctx.accounts.parcel_store, &programs_map);
+        let parcel = Empty {
+            account: dot::program::Parcel::load(&mut ctx.accounts.parcel, &programs_map),
+            bump: Some(ctx.bumps.parcel),
+        };
+
+        claim_parcel_handler(
+            payer.clone(),
+            owner.clone(),
+            authority.clone(),
+            world.clone(),
+            ctx.accounts.treasury.clone(),
+            parcel_store.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+        );
+
+        dot::program::World::store(world);
+
+        dot::program::HotaStore::store(parcel_store);
+
+        dot::program::Parcel::store(parcel.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (price : u64)]
+    pub struct SetParcelPrice<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        pub parcel: Box<Account<'info, dot::program::Parcel>>,
+    }
+
+    pub fn set_parcel_price(ctx: Context<SetParcelPrice>, price: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let parcel = dot::program::Parcel::load(&mut ctx.accounts.parcel, &programs_map);
+
+        set_parcel_price_handler(payer.clone(), owner.clone(), parcel.clone(), price);
+
+        dot::program::Parcel::store(parcel);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (max_price : u64 , min_price : u64)]
+    pub struct TradeParcel<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub old_owner: Signer<'info>,
+        #[account(mut)]
+        pub new_owner: Signer<'info>,
+        #[account(mut)]
+        pub parcel: Box<Account<'info, dot::program::Parcel>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn trade_parcel(ctx: Context<TradeParcel>, max_price: u64, min_price: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let old_owner = SeahorseSigner {
+            account: &ctx.accounts.old_owner,
+            programs: &programs_map,
+        };
+
+        let new_owner = SeahorseSigner {
+            account: &ctx.accounts.new_owner,
+            programs: &programs_map,
+        };
+
+        let parcel = dot::program::Parcel::load(&mut ctx.accounts.parcel, &programs_map);
+
+        trade_parcel_handler(
+            payer.clone(),
+            old_owner.clone(),
+            new_owner.clone(),
+            parcel.clone(),
+            max_price,
+            min_price,
+        );
+
+        dot::program::Parcel::store(parcel);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (delegate : Pubkey)]
+    pub struct AddParcelDelegate<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        pub parcel: Box<Account<'info, dot::program::Parcel>>,
+    }
+
+    pub fn add_parcel_delegate(ctx: Context<AddParcelDelegate>, delegate: Pubkey) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let parcel = dot::program::Parcel::load(&mut ctx.accounts.parcel, &programs_map);
+
+        add_parcel_delegate_handler(payer.clone(), owner.clone(), parcel.clone(), delegate);
+
+        dot::program::Parcel::store(parcel);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (delegate : Pubkey)]
+    pub struct RevokeParcelDelegate<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        pub parcel: Box<Account<'info, dot::program::Parcel>>,
+    }
+
+    pub fn revoke_parcel_delegate(
+        ctx: Context<RevokeParcelDelegate>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let parcel = dot::program::Parcel::load(&mut ctx.accounts.parcel, &programs_map);
+
+        revoke_parcel_delegate_handler(payer.clone(), owner.clone(), parcel.clone(), delegate);
+
+        dot::program::Parcel::store(parcel);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (index : u8)]
+    pub struct InitChildStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub parent: Box<Account<'info, dot::program::HotaStore>>,
+        # [account (init , space = dot :: program :: HotaStore :: space (parent . fan_out) , payer = payer , seeds = ["store" . as_bytes () . as_ref () , parent . key () . as_ref () , index . to_le_bytes () . as_ref ()] , bump)]
+        pub child: Box<Account<'info, dot::program::HotaStore>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_child_store(ctx: Context<InitChildStore>, index: u8) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let parent = dot::program::HotaStore::load(&mut ctx.accounts.parent, &programs_map);
+        ctx.accounts.child.version = dot::program::STORE_VERSION;
+        ctx.accounts.child.fan_out = parent.borrow().fan_out;
+
+        let child = Empty {
+            account: dot::program::HotaStore::load(&mut ctx.accounts.child, &programs_map),
+            bump: Some(ctx.bumps.child),
+        };
+
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        init_child_store_handler(
+            payer.clone(),
+            authority.clone(),
+            parent.clone(),
+            child.clone(),
+            parcel.clone(),
+            index,
+        );
+
+        dot::program::HotaStore::store(parent);
+
+        dot::program::HotaStore::store(child.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (xyz : XYZ)]
+    pub struct CreatePath<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn create_path<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePath<'info>>,
+        xyz: XYZ,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        create_path_handler(
+            payer.clone(),
+            authority.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+            xyz,
+        );
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (new_authority : Pubkey , with_blocks : bool)]
+    pub struct TransferStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn transfer_store(
+        ctx: Context<TransferStore>,
+        new_authority: Pubkey,
+        with_blocks: bool,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        transfer_store_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            new_authority,
+            with_blocks,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct CascadeStoreTransfer<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn cascade_store_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CascadeStoreTransfer<'info>>,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        cascade_store_transfer_handler(payer.clone(), hota_store.clone(), ctx.remaining_accounts);
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (price : u64)]
+    pub struct SetStorePrice<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn set_store_price(ctx: Context<SetStorePrice>, price: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        set_store_price_handler(payer.clone(), authority.clone(), hota_store.clone(), price);
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (max_price : u64 , min_price : u64 , with_blocks : bool)]
+    pub struct SellStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub seller: Signer<'info>,
+        #[account(mut)]
+        pub buyer: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn sell_store<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SellStore<'info>>,
+        max_price: u64,
+        min_price: u64,
+        with_blocks: bool,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let seller = SeahorseSigner {
+            account: &ctx.accounts.seller,
+            programs: &programs_map,
+        };
+
+        let buyer = SeahorseSigner {
+            account: &ctx.accounts.buyer,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        sell_store_handler(
+            payer.clone(),
+            seller.clone(),
+            buyer.clone(),
+            hota_store.clone(),
+            ctx.remaining_accounts,
+            max_price,
+            min_price,
+            with_blocks,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (xyz : XYZ , expected_revision : u64 , store_index : u8)]
+    pub struct MoveBlock<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        #[account(mut)]
+        pub block_history: Option<Box<Account<'info, dot::program::BlockHistory>>>,
+        # [account (mut , close = owner_block , seeds = ["cell" . as_bytes () . as_ref () , block . world . as_ref () , block . xyz . x . to_le_bytes () . as_ref () , block . xyz . y . to_le_bytes () . as_ref () , block . xyz . z . to_le_bytes () . as_ref ()] , bump)]
+        pub old_cell: Box<Account<'info, dot::program::BlockCell>>,
+        # [account (init , space = std :: mem :: size_of :: < dot :: program :: BlockCell > () + 8 , payer = payer , seeds = ["cell" . as_bytes () . as_ref () , block . world . as_ref () , xyz . x . to_le_bytes () . as_ref () , xyz . y . to_le_bytes () . as_ref () , xyz . z . to_le_bytes () . as_ref ()] , bump)]
+        pub new_cell: Box<Account<'info, dot::program::BlockCell>>,
+        #[account(mut)]
+        pub old_store: Option<Box<Account<'info, dot::program::HotaStore>>>,
+        #[account(mut)]
+        pub new_store: Option<Box<Account<'info, dot::program::HotaStore>>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn move_block<'info>(
+        ctx: Context<'_, '_, '_, 'info, MoveBlock<'info>>,
+        xyz: XYZ,
+        expected_revision: u64,
+        store_index: u8,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let block_history =
+            ctx.accounts.block_history.as_mut().map(|block_history| {
+                dot::program::BlockHistory::load(block_history, &programs_map)
+            });
+
+        let old_cell = dot::program::BlockCell::load(&mut ctx.accounts.old_cell, &programs_map);
+        let new_cell = Empty {
+            account: dot::program::BlockCell::load(&mut ctx.accounts.new_cell, &programs_map),
+            bump: Some(ctx.bumps.new_cell),
+        };
+
+        // A move within one store releases and occupies the slots of a
+        // single loaded copy, so neither change is lost to the other
+        let same_store = match (&ctx.accounts.old_store, &ctx.accounts.new_store) {
+            (Some(old_store), Some(new_store)) => old_store.key() == new_store.key(),
+            _ => false,
+        };
+
+        let old_store = ctx
+            .accounts
+            .old_store
+            .as_mut()
+            .map(|old_store| dot::program::HotaStore::load(old_store, &programs_map));
+
+        let new_store = if same_store {
+            old_store.clone()
+        } else {
+            ctx.accounts
+                .new_store
+                .as_mut()
+                .map(|new_store| dot::program::HotaStore::load(new_store, &programs_map))
+        };
+
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        move_block_handler(
+            payer.clone(),
+            owner_block.clone(),
+            authority.clone(),
+            block.clone(),
+            block_history.clone(),
+            old_cell.clone(),
+            new_cell.clone(),
+            old_store.clone(),
+            new_store.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+            xyz,
+            expected_revision,
+            store_index,
+        );
+
+        dot::program::Block::store(block);
+
+        if let Some(block_history) = block_history {
+            dot::program::BlockHistory::store(block_history);
+        }
+
+        dot::program::BlockCell::store(old_cell);
+
+        dot::program::BlockCell::store(new_cell.account);
+
+        if same_store {
+            drop(new_store);
+
+            if let Some(old_store) = old_store {
+                dot::program::HotaStore::store(old_store);
+            }
+
+            // Both fields hold the store, the later one is written last
+            ctx.accounts.old_store.as_ref().unwrap().exit(&crate::ID)?;
+            ctx.accounts.new_store.as_mut().unwrap().reload()?;
+        } else {
+            if let Some(old_store) = old_store {
+                dot::program::HotaStore::store(old_store);
+            }
+
+            if let Some(new_store) = new_store {
+                dot::program::HotaStore::store(new_store);
+            }
+        }
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct CloseBlock<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut, close = owner_block)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        # [account (mut , close = owner_block , seeds = ["cell" . as_bytes () . as_ref () , block . world . as_ref () , block . xyz . x . to_le_bytes () . as_ref () , block . xyz . y . to_le_bytes () . as_ref () , block . xyz . z . to_le_bytes () . as_ref ()] , bump)]
+        pub block_cell: Box<Account<'info, dot::program::BlockCell>>,
+        # [account (mut , seeds = [block . key () . as_ref () , "meta" . as_bytes () . as_ref ()] , bump)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub block_meta: UncheckedAccount<'info>,
+        # [account (mut , seeds = [block . key () . as_ref () , "history" . as_bytes () . as_ref ()] , bump)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub block_history: UncheckedAccount<'info>,
+        #[account(mut)]
+        pub hota_store: Option<Box<Account<'info, dot::program::HotaStore>>>,
+    }
+
+    pub fn close_block(ctx: Context<CloseBlock>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let block_cell = dot::program::BlockCell::load(&mut ctx.accounts.block_cell, &programs_map);
+        let hota_store = ctx
+            .accounts
+            .hota_store
+            .as_mut()
+            .map(|hota_store| dot::program::HotaStore::load(hota_store, &programs_map));
+
+        close_block_handler(
+            payer.clone(),
+            owner_block.clone(),
+            block.clone(),
+            block_cell.clone(),
+            ctx.accounts.block_meta.clone(),
+            ctx.accounts.block_history.clone(),
+            hota_store.clone(),
+        );
+
+        dot::program::Block::store(block);
+
+        dot::program::BlockCell::store(block_cell);
+
+        if let Some(hota_store) = hota_store {
+            dot::program::HotaStore::store(hota_store);
+        }
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct DetachStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub parent: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut)]
+        pub child: Box<Account<'info, dot::program::HotaStore>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+    }
+
+    pub fn detach_store(ctx: Context<DetachStore>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let parent = dot::program::HotaStore::load(&mut ctx.accounts.parent, &programs_map);
+        let child = dot::program::HotaStore::load(&mut ctx.accounts.child, &programs_map);
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        detach_store_handler(
+            payer.clone(),
+            authority.clone(),
+            parent.clone(),
+            child.clone(),
+            parcel.clone(),
+        );
+
+        dot::program::HotaStore::store(parent);
+
+        dot::program::HotaStore::store(child);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (index : u8)]
+    pub struct RelinkStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub child_authority: Signer<'info>,
+        #[account(mut)]
+        pub old_parent: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut)]
+        pub new_parent: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut)]
+        pub child: Box<Account<'info, dot::program::HotaStore>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+    }
+
+    pub fn relink_store<'info>(
+        ctx: Context<'_, '_, '_, 'info, RelinkStore<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let child_authority = SeahorseSigner {
+            account: &ctx.accounts.child_authority,
+            programs: &programs_map,
+        };
+
+        let old_parent = dot::program::HotaStore::load(&mut ctx.accounts.old_parent, &programs_map);
+        let new_parent = dot::program::HotaStore::load(&mut ctx.accounts.new_parent, &programs_map);
+        let child = dot::program::HotaStore::load(&mut ctx.accounts.child, &programs_map);
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        relink_store_handler(
+            payer.clone(),
+            authority.clone(),
+            child_authority.clone(),
+            old_parent.clone(),
+            new_parent.clone(),
+            child.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+            index,
+        );
+
+        dot::program::HotaStore::store(old_parent);
+
+        dot::program::HotaStore::store(new_parent);
+
+        dot::program::HotaStore::store(child);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct CloseStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut, close = receiver)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub receiver: UncheckedAccount<'info>,
+        # [account (seeds = ["world" . as_bytes () . as_ref () , hota_store . key () . as_ref ()] , bump)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub world: UncheckedAccount<'info>,
+        # [account (seeds = ["parcel" . as_bytes () . as_ref () , hota_store . key () . as_ref ()] , bump)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub parcel: UncheckedAccount<'info>,
+    }
+
+    pub fn close_store(ctx: Context<CloseStore>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        close_store_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            ctx.accounts.receiver.clone(),
+            ctx.accounts.world.clone(),
+            ctx.accounts.parcel.clone(),
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (depth : u8 , kind : u8 , origin : XYZ , seed_random : u128)]
+    pub struct InitSpatialStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        # [account (init , space = dot :: program :: HotaStore :: space (dot :: program :: store_kind_fan_out (kind)) , payer = payer , seeds = ["store" . as_bytes () . as_ref () , depth . to_le_bytes () . as_ref () , seed_random . to_le_bytes () . as_ref ()] , bump)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_spatial_store(
+        ctx: Context<InitSpatialStore>,
+        depth: u8,
+        kind: u8,
+        origin: XYZ,
+        seed_random: u128,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        ctx.accounts.hota_store.version = dot::program::STORE_VERSION;
+        ctx.accounts.hota_store.fan_out = dot::program::store_kind_fan_out(kind);
+
+        let hota_store = Empty {
+            account: dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map),
+            bump: Some(ctx.bumps.hota_store),
+        };
+
+        init_spatial_store_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            depth,
+            kind,
+            origin,
+            seed_random,
+        );
+
+        dot::program::HotaStore::store(hota_store.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (world : Pubkey)]
+    pub struct SetStoreLazy<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn set_store_lazy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetStoreLazy<'info>>,
+        world: Pubkey,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        set_store_lazy_handler(
+            payer.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            ctx.remaining_accounts,
+            world,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (index : u8)]
+    pub struct ClaimStoreSlot<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+        # [account (init , space = std :: mem :: size_of :: < dot :: program :: StoreSlot > () + 8 , payer = payer , seeds = [hota_store . key () . as_ref () , "slot" . as_bytes () . as_ref () , index . to_le_bytes () . as_ref ()] , bump)]
+        pub store_slot: Box<Account<'info, dot::program::StoreSlot>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn claim_store_slot<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimStoreSlot<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+        let store_slot = Empty {
+            account: dot::program::StoreSlot::load(&mut ctx.accounts.store_slot, &programs_map),
+            bump: Some(ctx.bumps.store_slot),
+        };
+
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        claim_store_slot_handler(
+            payer.clone(),
+            owner_block.clone(),
+            authority.clone(),
+            hota_store.clone(),
+            block.clone(),
+            store_slot.clone(),
+            parcel.clone(),
+            ctx.remaining_accounts,
+            index,
+        );
+
+        dot::program::Block::store(block);
+
+        dot::program::StoreSlot::store(store_slot.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (index : u8)]
+    pub struct InitLazyChildStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+        pub parent: Box<Account<'info, dot::program::HotaStore>>,
+        # [account (init , space = dot :: program :: HotaStore :: space (parent . fan_out) , payer = payer , seeds = ["store" . as_bytes () . as_ref () , parent . key () . as_ref () , index . to_le_bytes () . as_ref ()] , bump)]
+        pub child: Box<Account<'info, dot::program::HotaStore>>,
+        pub parcel: Option<Box<Account<'info, dot::program::Parcel>>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_lazy_child_store(ctx: Context<InitLazyChildStore>, index: u8) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let authority = SeahorseSigner {
+            account: &ctx.accounts.authority,
+            programs: &programs_map,
+        };
+
+        let parent = dot::program::HotaStore::load(&mut ctx.accounts.parent, &programs_map);
+        ctx.accounts.child.version = dot::program::STORE_VERSION;
+        ctx.accounts.child.fan_out = parent.borrow().fan_out;
+
+        let child = Empty {
+            account: dot::program::HotaStore::load(&mut ctx.accounts.child, &programs_map),
+            bump: Some(ctx.bumps.child),
+        };
+
+        let parcel = ctx
+            .accounts
+            .parcel
+            .as_mut()
+            .map(|parcel| dot::program::Parcel::load(parcel, &programs_map));
+
+        init_lazy_child_store_handler(
+            payer.clone(),
+            authority.clone(),
+            parent.clone(),
+            child.clone(),
+            parcel.clone(),
+            index,
+        );
+
+        dot::program::HotaStore::store(child.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct SyncStoreSlots<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn sync_store_slots<'info>(
+        ctx: Context<'_, '_, '_, 'info, SyncStoreSlots<'info>>,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        sync_store_slots_handler(payer.clone(), hota_store.clone(), ctx.remaining_accounts);
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct VacateStoreSlot<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner_block: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+        #[account(mut, close = owner_block)]
+        pub store_slot: Box<Account<'info, dot::program::StoreSlot>>,
+        #[account(mut)]
+        pub block: Box<Account<'info, dot::program::Block>>,
+    }
+
+    pub fn vacate_store_slot(ctx: Context<VacateStoreSlot>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let owner_block = SeahorseSigner {
+            account: &ctx.accounts.owner_block,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+        let store_slot = dot::program::StoreSlot::load(&mut ctx.accounts.store_slot, &programs_map);
+        let block = dot::program::Block::load(&mut ctx.accounts.block, &programs_map);
+
+        vacate_store_slot_handler(
+            payer.clone(),
+            owner_block.clone(),
+            hota_store.clone(),
+            store_slot.clone(),
+            block.clone(),
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        dot::program::StoreSlot::store(store_slot);
+
+        dot::program::Block::store(block);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (start : u16 , end : u16)]
+    pub struct RefreshStoreHash<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn refresh_store_hash<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefreshStoreHash<'info>>,
+        start: u16,
+        end: u16,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        refresh_store_hash_handler(
+            payer.clone(),
+            hota_store.clone(),
+            ctx.remaining_accounts,
+            start,
+            end,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    # [instruction (start : u16 , end : u16)]
+    pub struct RefreshStoreLod<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub hota_store: Box<Account<'info, dot::program::HotaStore>>,
+    }
+
+    pub fn refresh_store_lod<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefreshStoreLod<'info>>,
+        start: u16,
+        end: u16,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let hota_store = dot::program::HotaStore::load(&mut ctx.accounts.hota_store, &programs_map);
+
+        refresh_store_lod_handler(
+            payer.clone(),
+            hota_store.clone(),
+            ctx.remaining_accounts,
+            start,
+            end,
+        );
+
+        dot::program::HotaStore::store(hota_store);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct MigrateBlock<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub block: UncheckedAccount<'info>,
+        pub world: Box<Account<'info, dot::program::HotaStore>>,
+        # [account (seeds = ["world" . as_bytes () . as_ref () , world . key () . as_ref ()] , bump)]
+        pub world_settings: Box<Account<'info, dot::program::World>>,
+        #[account(mut)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub block_cell: UncheckedAccount<'info>,
+        #[account(mut)]
+        pub hota_store: Option<Box<Account<'info, dot::program::HotaStore>>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn migrate_block<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateBlock<'info>>,
+    ) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let hota_store = ctx
+            .accounts
+            .hota_store
+            .as_mut()
+            .map(|hota_store| dot::program::HotaStore::load(hota_store, &programs_map));
+
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        migrate_block_handler(
+            payer.clone(),
+            owner.clone(),
+            ctx.accounts.block.clone(),
+            ctx.accounts.world.key(),
+            ctx.accounts.block_cell.clone(),
+            hota_store.clone(),
+            ctx.remaining_accounts,
+        );
+
+        if let Some(hota_store) = hota_store {
+            dot::program::HotaStore::store(hota_store);
+        }
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct MigrateStore<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        #[doc = "CHECK: This account is unchecked."]
+        pub hota_store: UncheckedAccount<'info>,
+        pub parent: Option<Box<Account<'info, dot::program::HotaStore>>>,
+        pub authority: Option<Signer<'info>>,
+        pub program_data: Option<Box<Account<'info, ProgramData>>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn migrate_store(ctx: Context<MigrateStore>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let parent = ctx
+            .accounts
+            .parent
+            .as_mut()
+            .map(|parent| dot::program::HotaStore::load(parent, &programs_map));
+
+        let authority = ctx
+            .accounts
+            .authority
+            .as_ref()
+            .map(|authority| SeahorseSigner {
+                account: authority,
+                programs: &programs_map,
+            });
+
+        migrate_store_handler(
+            payer.clone(),
+            ctx.accounts.hota_store.clone(),
+            parent.clone(),
+            authority.clone(),
+            ctx.accounts.program_data.clone(),
+        );
+
+        return Ok(());
+    }
 }
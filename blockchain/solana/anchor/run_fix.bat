@@ -1 +0,0 @@
-call python auto_fix_anchor.py
\ No newline at end of file